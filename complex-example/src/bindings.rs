@@ -25,6 +25,19 @@ impl __JNI_MyOtherStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub const JAVA_CLASS: &str = "com/example/MyOtherStruct";
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -43,6 +56,22 @@ impl __JNI_MyOtherStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub unsafe fn __set(&mut self, base: MyOtherStruct) {
+        self.a = base.a;
+        *self.b = base.b;
+    }
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -195,7 +224,7 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1get_1b<'local,
     class: JClass<'local>,
     ptr: jlong,
 ) -> jlong {
-    let it = &mut *(ptr as *mut __JNI_MyOtherStruct);
+    let it = &*(ptr as *mut __JNI_MyOtherStruct);
 
     it.b as jlong
 }
@@ -212,10 +241,10 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1get_1b<'local,
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jlong {
-    
+pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jobject {
+    remember_java_vm(&env);
     let it = __JNI_MyOtherStruct::__wrapped_new();
-    (Box::leak(Box::new(it)) as *mut __JNI_MyOtherStruct) as jlong
+    (Box::leak(Box::new(it)) as *mut __JNI_MyOtherStruct) as jobject
 }
 
 #[unsafe(no_mangle)]
@@ -231,7 +260,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'loc
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1only<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, message: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let message = env.get_string(&message).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_say_only(message.clone())
@@ -250,7 +280,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1only<'loc
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, p2: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let p2 = env.get_string(&p2).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_say(p2.clone())
@@ -269,7 +300,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say<'local, >(
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1with<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, p1: jlong, p2: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let p1 = &*(p1 as *mut MyStruct);
     let p2 = env.get_string(&p2).unwrap().to_str().unwrap().to_string();
 
@@ -288,7 +320,7 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1with<'loc
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1free<'local, >(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
+pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1free<'local, >(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
     let it = Box::from_raw(ptr as *mut __JNI_MyOtherStruct);
     let _ = Box::from_raw(it.b);
 }
@@ -322,6 +354,19 @@ impl __JNI_MyStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub const JAVA_CLASS: &str = "com/example/MyStruct";
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -342,6 +387,24 @@ impl __JNI_MyStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub unsafe fn __set(&mut self, base: MyStruct) {
+        self.a = base.a;
+        self.b = base.b;
+        self.c = base.c;
+        *self.people = base.people;
+    }
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -558,7 +621,7 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1get_1people<'local,
     class: JClass<'local>,
     ptr: jlong,
 ) -> jlong {
-    let it = &mut *(ptr as *mut __JNI_MyStruct);
+    let it = &*(ptr as *mut __JNI_MyStruct);
 
     it.people as jlong
 }
@@ -575,10 +638,10 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1get_1people<'local,
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jlong {
-    
+pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jobject {
+    remember_java_vm(&env);
     let it = __JNI_MyStruct::__wrapped_new();
-    (Box::leak(Box::new(it)) as *mut __JNI_MyStruct) as jlong
+    (Box::leak(Box::new(it)) as *mut __JNI_MyStruct) as jobject
 }
 
 #[unsafe(no_mangle)]
@@ -594,7 +657,8 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1say_1to_1all<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, msg: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyStruct);
     let msg = env.get_string(&msg).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_say_to_all(msg.clone())
@@ -612,7 +676,7 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1say_1to_1all<'local
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1free<'local, >(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
+pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1free<'local, >(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
     let it = Box::from_raw(ptr as *mut __JNI_MyStruct);
     let _ = Box::from_raw(it.people);
 }
@@ -640,6 +704,19 @@ impl __JNI_StrVec {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub const JAVA_CLASS: &str = "com/example/StrVec";
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -655,6 +732,21 @@ impl __JNI_StrVec {
         self.__inner.clone()
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub unsafe fn __set(&mut self, base: StrVec) {
+        self.__inner = base;
+    }
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -684,7 +776,13 @@ impl __JNI_StrVec {
     unsafe_op_in_unsafe_fn,
 )]
     pub unsafe fn __wrapped_push(&mut self, item: String) -> () {
-        StrVec::push(&mut self.to_rust(), item).clone()
+        let mut base = self.to_rust();
+        let val = {
+            StrVec::push(&mut base, item).clone()
+        };
+
+        self.__set(base);
+        val
     }
 }
 
@@ -700,10 +798,10 @@ impl __JNI_StrVec {
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_StrVec_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jlong {
-    
+pub unsafe extern "system" fn Java_com_example_StrVec_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jobject {
+    remember_java_vm(&env);
     let it = __JNI_StrVec::__wrapped_new();
-    (Box::leak(Box::new(it)) as *mut __JNI_StrVec) as jlong
+    (Box::leak(Box::new(it)) as *mut __JNI_StrVec) as jobject
 }
 
 #[unsafe(no_mangle)]
@@ -719,7 +817,8 @@ pub unsafe extern "system" fn Java_com_example_StrVec_jni_1init_1new<'local, >(m
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_StrVec_jni_1push<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, item: JString<'local>) -> () {
-    let it = &mut *(ptr as *mut __JNI_StrVec);
+    remember_java_vm(&env);
+let it = &mut *(ptr as *mut __JNI_StrVec);
     let item = env.get_string(&item).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_push(item.clone())
@@ -737,7 +836,7 @@ pub unsafe extern "system" fn Java_com_example_StrVec_jni_1push<'local, >(mut en
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_StrVec_jni_1free<'local, >(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
+pub unsafe extern "system" fn Java_com_example_StrVec_jni_1free<'local, >(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
     let it = Box::from_raw(ptr as *mut __JNI_StrVec);
     
 }
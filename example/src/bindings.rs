@@ -25,6 +25,19 @@ impl __JNI_MyOtherStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub const JAVA_CLASS: &str = "com/example/MyOtherStruct";
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -43,6 +56,22 @@ impl __JNI_MyOtherStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub unsafe fn __set(&mut self, base: MyOtherStruct) {
+        self.a = base.a;
+        *self.b = base.b;
+    }
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -195,7 +224,7 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1get_1b<'local,
     class: JClass<'local>,
     ptr: jlong,
 ) -> jlong {
-    let it = &mut *(ptr as *mut __JNI_MyOtherStruct);
+    let it = &*(ptr as *mut __JNI_MyOtherStruct);
 
     it.b as jlong
 }
@@ -212,10 +241,10 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1get_1b<'local,
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jlong {
-    
+pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jobject {
+    remember_java_vm(&env);
     let it = __JNI_MyOtherStruct::__wrapped_new();
-    (Box::leak(Box::new(it)) as *mut __JNI_MyOtherStruct) as jlong
+    (Box::leak(Box::new(it)) as *mut __JNI_MyOtherStruct) as jobject
 }
 
 #[unsafe(no_mangle)]
@@ -231,7 +260,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1init_1new<'loc
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1only<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, message: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let message = env.get_string(&message).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_say_only(message.clone())
@@ -250,7 +280,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1only<'loc
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, p2: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let p2 = env.get_string(&p2).unwrap().to_str().unwrap().to_string();
 
     it.__wrapped_say(p2.clone())
@@ -269,7 +300,8 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say<'local, >(
     unsafe_op_in_unsafe_fn,
 )]
 pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1with<'local, >(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong, p1: jlong, p2: JString<'local>) -> () {
-    let it = &*(ptr as *mut __JNI_MyOtherStruct);
+    remember_java_vm(&env);
+let it = &*(ptr as *mut __JNI_MyOtherStruct);
     let p1 = &*(p1 as *mut MyStruct);
     let p2 = env.get_string(&p2).unwrap().to_str().unwrap().to_string();
 
@@ -288,7 +320,7 @@ pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1say_1with<'loc
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1free<'local, >(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
+pub unsafe extern "system" fn Java_com_example_MyOtherStruct_jni_1free<'local, >(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
     let it = Box::from_raw(ptr as *mut __JNI_MyOtherStruct);
     let _ = Box::from_raw(it.b);
 }
@@ -320,6 +352,19 @@ impl __JNI_MyStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub const JAVA_CLASS: &str = "com/example/MyStruct";
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -339,6 +384,23 @@ impl __JNI_MyStruct {
         }
     }
 
+    #[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs,
+    unsafe_op_in_unsafe_fn,
+)]
+    pub unsafe fn __set(&mut self, base: MyStruct) {
+        self.a = base.a;
+        self.b = base.b;
+        self.c = base.c;
+    }
+
     #[allow(
     unused_mut,
     unused_variables,
@@ -510,10 +572,10 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1get_1c<'local, >(
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jlong {
-    
+pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >(mut env: JNIEnv<'local>, obj: JObject<'local>, ) -> jobject {
+    remember_java_vm(&env);
     let it = __JNI_MyStruct::__wrapped_new();
-    (Box::leak(Box::new(it)) as *mut __JNI_MyStruct) as jlong
+    (Box::leak(Box::new(it)) as *mut __JNI_MyStruct) as jobject
 }
 
 #[unsafe(no_mangle)]
@@ -528,7 +590,7 @@ pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1init_1new<'local, >
     missing_docs,
     unsafe_op_in_unsafe_fn,
 )]
-pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1free<'local, >(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
+pub unsafe extern "system" fn Java_com_example_MyStruct_jni_1free<'local, >(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {
     let it = Box::from_raw(ptr as *mut __JNI_MyStruct);
     
 }
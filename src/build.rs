@@ -7,9 +7,13 @@ use glob::glob;
 use regex::Regex;
 
 use crate::{
+    class::{
+        expr::Item, resolve_bases, resolve_generics, resolve_impls, resolve_overloads,
+        resolve_values,
+    },
     codegen::{cx::Generator, java::gen_java_code, rust::gen_rust_code},
     equals_throw, if_else,
-    parser::classes,
+    parser::items,
 };
 
 /// The build config.
@@ -32,6 +36,15 @@ pub struct BindgenConfig {
 
     /// Generate Kotlin code?
     pub kotlin: bool,
+
+    /// Instrument natives with `tracing` spans and call stats?
+    pub instrument: bool,
+
+    /// Track the objects made for Java, to find leaks?
+    pub track_leaks: bool,
+
+    /// Generate the `NativeLogging` class?
+    pub logging: bool,
 }
 
 impl BindgenConfig {
@@ -44,6 +57,9 @@ impl BindgenConfig {
             bindings: PathBuf::new(),
             annotations: false,
             kotlin: false,
+            instrument: false,
+            track_leaks: false,
+            logging: false,
         }
     }
 
@@ -129,6 +145,30 @@ impl BindgenConfig {
         self
     }
 
+    /// Enable/disable instrumenting natives. Every call records a `tracing`
+    /// span with its class, method and arguments (so rs4j needs the `tracing`
+    /// feature), and is counted and timed in [`crate::internal::stats`].
+    pub fn instrument(mut self, enable: bool) -> Self {
+        self.instrument = enable;
+        self
+    }
+
+    /// Enable/disable tracking the objects made for Java. Every object is
+    /// registered in [`crate::internal::leaks`] until it's freed, so the ones
+    /// that are never freed can be found.
+    pub fn track_leaks(mut self, enable: bool) -> Self {
+        self.track_leaks = enable;
+        self
+    }
+
+    /// Enable/disable generating the `NativeLogging` class, which sets up
+    /// [`crate::internal::logging`] from Java. Its natives are only there with
+    /// the `log` feature.
+    pub fn logging(mut self, enable: bool) -> Self {
+        self.logging = enable;
+        self
+    }
+
     /// Generate bindings.
     pub fn generate(self) -> Result<Self> {
         equals_throw!(
@@ -181,22 +221,39 @@ impl BindgenConfig {
             with_annotations: self.annotations,
             library: env::var("CARGO_PKG_NAME")?,
             kotlin: self.kotlin,
+            instrument: self.instrument,
+            track_leaks: self.track_leaks,
+            logging: self.logging,
             out_dir: self.output.join(if_else!(self.kotlin, "kotlin", "java")),
         };
 
         let mut exprs = Vec::new();
+        let mut impls = Vec::new();
+        let mut externs = Vec::new();
 
         for file in self.files.clone() {
             let data = fs::read_to_string(file)?;
 
-            exprs.append(&mut classes(data.as_str())?);
+            for item in items(data.as_str())? {
+                match item {
+                    Item::Class(it) => exprs.push(it),
+                    Item::Impl(it) => impls.push(it),
+                    Item::Extern(it) => externs.push(it),
+                }
+            }
         }
 
         for item in &mut exprs {
             item.package = cx.package.clone();
         }
 
-        gen_rust_code(&cx, &exprs, &self.bindings)?;
+        resolve_impls(&mut exprs, &impls)?;
+        resolve_generics(&mut exprs)?;
+        resolve_overloads(&mut exprs)?;
+        resolve_bases(&mut exprs)?;
+        resolve_values(&mut exprs)?;
+
+        gen_rust_code(&cx, &exprs, &externs, &self.bindings)?;
         gen_java_code(&cx, &exprs)?;
 
         Ok(())
@@ -208,6 +265,9 @@ impl BindgenConfig {
             with_annotations: self.annotations,
             library: env::var("CARGO_PKG_NAME")?,
             kotlin: self.kotlin,
+            instrument: self.instrument,
+            track_leaks: self.track_leaks,
+            logging: self.logging,
             out_dir: self.output.join(if_else!(self.kotlin, "kotlin", "java")),
         };
 
@@ -1,8 +1,11 @@
 //! Arguments
 
-use crate::codegen::java::JExpr;
+use crate::codegen::java::{JExpr, JType};
 
-use super::ty::Type;
+use super::{
+    constant::Literal,
+    ty::{Type, TypeKind},
+};
 
 /// A function argument.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -21,9 +24,23 @@ pub struct FunctionArg {
 
     /// Does it need a `.into()`?
     pub into: bool,
+
+    /// The default value, which Java gets an overload without it for.
+    pub default: Option<Literal>,
+
+    /// Is this a varargs [`TypeKind::Slice`], like `...String`?
+    pub varargs: bool,
 }
 
 impl FunctionArg {
+    /// Get the type of this as a parameter in Java.
+    pub fn java_type(&self) -> JType {
+        match &self.ty.kind {
+            TypeKind::Slice(item) if self.varargs => JType::Varargs(Box::new(item.j_type())),
+            _ => self.ty.j_type(),
+        }
+    }
+
     /// Get the reference for Java
     pub fn java_name(&self) -> JExpr {
         if self.ty.kind.is_primitive() {
@@ -49,6 +49,8 @@ pub fn free_method_java_wrapper() -> JMember {
         private: false,
         args: Vec::new(),
         is_override: false,
+        is_suspend: false,
+        is_operator: false,
         generics: BTreeMap::new(),
         code: vec![JExpr::Call(JCall {
             target: "jni_free".into(),
@@ -57,19 +59,61 @@ pub fn free_method_java_wrapper() -> JMember {
     })
 }
 
+/// Generate the native method code for Java registering an object's guard
+pub fn guard_method_java() -> JMember {
+    JMember::ExternMethod(JExternMethod {
+        name: "jni_guard".into(),
+        private: true,
+        is_static: true,
+        ret: JType::Void,
+        args: vec![("ptr".into(), JType::Long)],
+    })
+}
+
+/// Generate the call registering an object's guard, for its constructors
+pub fn guard_call_java() -> JExpr {
+    JExpr::Call(JCall {
+        target: "jni_guard".into(),
+        args: vec![JExpr::Name("__ptr".into())],
+    })
+}
+
+/// Generate the native method code for Rust registering an object's guard
+pub fn guard_method_rust(cls: &ClassCtx) -> String {
+    let method = cls.method_name("jni_guard");
+
+    let generics = cls
+        .generics
+        .iter()
+        .map(|v| v.code())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!("{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{method}<'local, {generics}>(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {{
+    register_object_guard(ptr);
+}}")
+}
+
 /// Generate the `free()` native method code for Rust
 pub fn free_method_rust(cls: &ClassCtx, fields: &Vec<Field>) -> String {
     let method = cls.method_name("jni_free");
+    let trace = cls.instrument_block("free", &[]);
+    let guard = cls.guard_block(true);
     let class = &cls.name_generics();
     let mut frees = Vec::new();
 
     for field in fields {
         if !field.is_primitive() {
-            frees.push(format!("let _ = Box::from_raw(it.{});", field.name));
+            let free = cls.unleak(&format!("it.{}", field.name));
+
+            frees.push(format!("{free}let _ = Box::from_raw(it.{});", field.name));
         }
     }
 
     let frees = frees.join("\n");
+    let unleak = cls.unleak("ptr");
+    let unguard = cls.unguard();
 
     let generics = cls
         .generics
@@ -80,8 +124,8 @@ pub fn free_method_rust(cls: &ClassCtx, fields: &Vec<Field>) -> String {
 
     // FIXME: This WILL cause a memory leak if an object is more than two levels deep. FIX THIS!
     format!("{RUST_BRIDGE_HEAD}
-pub unsafe extern \"system\" fn Java_{method}<'local, {generics}>(_env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {{
-    let it = Box::from_raw(ptr as *mut {class});
+pub unsafe extern \"system\" fn Java_{method}<'local, {generics}>(mut env: JNIEnv<'local>, _class: JClass<'local>, ptr: jlong) {{
+{trace}{guard}    {unleak}{unguard}let it = Box::from_raw(ptr as *mut {class});
     {frees}
 }}")
 }
@@ -95,11 +139,13 @@ pub fn of_func(cx: &ClassCtx, fields: &Vec<Field>) -> String {
         if field.is_primitive() {
             field_setters.push(format!("            {0}: base.{0}.clone(),", field.name));
         } else {
-            field_setters.push(format!(
-                "            {0}: Box::leak(Box::new(base.{0})) as *mut {1},",
-                field.name,
-                field.ty.full_type()
-            ));
+            let ptr = cx.leak(
+                &format!("base.{}", field.name),
+                &field.ty.full_type(),
+                &format!("{}.{}", cx.java_name(), field.name),
+            );
+
+            field_setters.push(format!("            {}: {ptr},", field.name));
         }
     }
 
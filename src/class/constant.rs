@@ -0,0 +1,232 @@
+//! Constants.
+
+use super::ty::{Type, TypeKind};
+use crate::{
+    codegen::{
+        cx::Generator,
+        java::{JField, JMember},
+    },
+    if_else,
+};
+
+/// A literal value.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Literal {
+    /// An integer, like `16`.
+    Int(i128),
+
+    /// A float, like `1.5`, as it's written.
+    Float(String),
+
+    /// A boolean.
+    Bool(bool),
+
+    /// A string, with its escapes resolved.
+    Str(String),
+
+    /// A character.
+    Char(char),
+}
+
+impl Literal {
+    /// Does this fit in a [`TypeKind`]?
+    pub fn fits(&self, kind: &TypeKind) -> bool {
+        match (self, kind) {
+            (Self::Int(it), kind) => match kind {
+                TypeKind::I8 => i8::try_from(*it).is_ok(),
+                TypeKind::I16 => i16::try_from(*it).is_ok(),
+                TypeKind::I32 => i32::try_from(*it).is_ok(),
+                TypeKind::I64 => i64::try_from(*it).is_ok(),
+                TypeKind::U8 => u8::try_from(*it).is_ok(),
+                TypeKind::U16 => u16::try_from(*it).is_ok(),
+                TypeKind::U32 => u32::try_from(*it).is_ok(),
+                TypeKind::U64 => u64::try_from(*it).is_ok(),
+                TypeKind::F32 | TypeKind::F64 => true,
+                _ => false,
+            },
+
+            (Self::Float(_), kind) => matches!(kind, TypeKind::F32 | TypeKind::F64),
+            (Self::Bool(_), kind) => *kind == TypeKind::Bool,
+            (Self::Str(_), kind) => *kind == TypeKind::String,
+
+            // Java's `char` is a single UTF-16 unit.
+            (Self::Char(it), kind) => *kind == TypeKind::Char && it.len_utf16() == 1,
+        }
+    }
+
+    /// Does this fit in a [`TypeKind`] once it's in Java? Java's integers are
+    /// all signed, so unsigned ones can only go up to the signed maximum.
+    pub fn fits_java(&self, kind: &TypeKind) -> bool {
+        let signed = match kind {
+            TypeKind::U8 => TypeKind::I8,
+            TypeKind::U16 => TypeKind::I16,
+            TypeKind::U32 => TypeKind::I32,
+            TypeKind::U64 => TypeKind::I64,
+            kind => kind.clone(),
+        };
+
+        self.fits(kind) && self.fits(&signed)
+    }
+
+    /// Resolve the escapes in the contents of a string or character literal,
+    /// the way Rust does.
+    pub fn unescape(raw: &str) -> Result<String, &'static str> {
+        let mut val = String::new();
+        let mut chars = raw.chars().peekable();
+
+        while let Some(it) = chars.next() {
+            if it != '\\' {
+                val.push(it);
+                continue;
+            }
+
+            let it = match chars.next() {
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('0') => '\0',
+                Some(it @ ('\\' | '\'' | '"')) => it,
+
+                Some('x') => {
+                    let hex = chars.by_ref().take(2).collect::<String>();
+
+                    match u8::from_str_radix(&hex, 16) {
+                        Ok(it) if hex.len() == 2 && it <= 0x7f => it as char,
+                        _ => return Err("a \\x escape up to \\x7f"),
+                    }
+                }
+
+                Some('u') => {
+                    if chars.next() != Some('{') {
+                        return Err("a \\u{...} escape");
+                    }
+
+                    let mut hex = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('_') => {}
+                            Some(it) => hex.push(it),
+                            None => return Err("a \\u{...} escape"),
+                        }
+                    }
+
+                    match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        Some(it) if hex.len() <= 6 => it,
+                        _ => return Err("a \\u{...} escape of a valid character"),
+                    }
+                }
+
+                // A line continuation skips the line break and the indentation after it.
+                Some('\n') => {
+                    while chars.next_if(|it| it.is_whitespace()).is_some() {}
+                    continue;
+                }
+
+                _ => return Err("a valid escape"),
+            };
+
+            val.push(it);
+        }
+
+        Ok(val)
+    }
+
+    /// Escape a string or character for a Java or Kotlin literal quoted with
+    /// `quote`. Anything but printable ASCII becomes a `\uXXXX` escape, with
+    /// characters outside the BMP as surrogate pairs.
+    fn escape(val: &str, quote: char, kotlin: bool) -> String {
+        let mut out = String::new();
+
+        for it in val.chars() {
+            match it {
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                '\\' => out.push_str("\\\\"),
+                '$' if kotlin && quote == '"' => out.push_str("\\$"),
+                it if it == quote => {
+                    out.push('\\');
+                    out.push(it);
+                }
+                ' '..='~' => out.push(it),
+                it => {
+                    for unit in it.encode_utf16(&mut [0; 2]) {
+                        out.push_str(&format!("\\u{unit:04x}"));
+                    }
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Get the value as a [`TypeKind`] in Java or Kotlin. Unsigned integers
+    /// wrap around like they do everywhere else, since Java doesn't have them
+    /// (constants are checked with [`Self::fits_java`] instead).
+    pub fn java_value(&self, kind: &TypeKind, cx: &Generator) -> String {
+        // Java doesn't narrow `int` literals passed as arguments.
+        let cast = |ty: &str| if_else!(cx.kotlin, String::new(), format!("({ty}) "));
+
+        match (self, kind) {
+            (Self::Int(it), TypeKind::F32) => format!("{it}.0f"),
+            (Self::Int(it), TypeKind::F64) => format!("{it}.0"),
+            (Self::Int(it), TypeKind::I8 | TypeKind::U8) => {
+                format!("{}{}", cast("byte"), *it as i8)
+            }
+            (Self::Int(it), TypeKind::I16 | TypeKind::U16) => {
+                format!("{}{}", cast("short"), *it as i16)
+            }
+            (Self::Int(it), TypeKind::I32 | TypeKind::U32) => (*it as i32).to_string(),
+            (Self::Int(it), _) => format!("{}L", *it as i64),
+            (Self::Float(it), TypeKind::F32) => format!("{it}f"),
+            (Self::Float(it), _) => it.clone(),
+            (Self::Bool(it), _) => it.to_string(),
+            (Self::Str(it), _) => format!("\"{}\"", Self::escape(it, '"', cx.kotlin)),
+            (Self::Char(it), _) => format!("'{}'", Self::escape(&it.to_string(), '\'', cx.kotlin)),
+        }
+    }
+}
+
+/// A constant in a class, like `const MAX: u32 = 16;`. Java gets a
+/// `static final` compile-time constant, so there's no native call.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Constant {
+    /// The name of this constant.
+    pub name: String,
+
+    /// This constant's [`Type`].
+    pub ty: Type,
+
+    /// The value.
+    pub value: Literal,
+}
+
+impl Constant {
+    /// Create a new [`Constant`], checking that the value fits its type in
+    /// Java, so it doesn't change on the way there.
+    pub fn new(name: String, ty: Type, value: Literal) -> Result<Self, &'static str> {
+        if !value.fits(&ty.kind) {
+            return Err("a literal matching the constant's type");
+        }
+
+        if !value.fits_java(&ty.kind) {
+            return Err("an unsigned constant no larger than Java's signed maximum");
+        }
+
+        Ok(Self { name, ty, value })
+    }
+
+    /// Generate the Java code for this constant.
+    pub fn java_code(&self, cx: &Generator) -> JMember {
+        JMember::Field(JField {
+            name: self.name.clone(),
+            ty: self.ty.j_type(),
+            private: false,
+            is_static: true,
+            is_final: true,
+            value: Some(self.value.java_value(&self.ty.kind, cx)),
+        })
+    }
+}
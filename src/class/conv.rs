@@ -2,7 +2,10 @@
 
 use crate::if_else;
 
-use super::ty::{Type, TypeKind};
+use super::{
+    generic::TypeGeneric,
+    ty::{Type, TypeKind},
+};
 
 /// Generate conversion code for a variable.
 pub fn conversion_method(var: impl AsRef<str>, ty: &Type, mutable: bool) -> Option<String> {
@@ -14,6 +17,17 @@ pub fn conversion_method(var: impl AsRef<str>, ty: &Type, mutable: bool) -> Opti
             "    let {mut_}{} = env.get_string(&{}).unwrap().to_str().unwrap().to_string();",
             var, var
         )),
+        TypeKind::Duration
+        | TypeKind::SystemTime
+        | TypeKind::PathBuf
+        | TypeKind::Tuple(_)
+        | TypeKind::Value(_) => Some(format!(
+            "    let {mut_}{} = {};",
+            var,
+            ty.kind
+                .from_jobject_call("&mut env", &format!("&{var}"))
+                .unwrap()
+        )),
         TypeKind::Other(_) => Some(format!(
             "    let {} = &{mut_}*({} as *mut {});",
             var,
@@ -23,3 +37,106 @@ pub fn conversion_method(var: impl AsRef<str>, ty: &Type, mutable: bool) -> Opti
         _ => None,
     }
 }
+
+/// Generate conversion code for a Java array argument, copying it into a `Vec`.
+/// Elements of generated classes are read through their pointers. If the array
+/// can't be converted, the native returns early.
+pub fn array_conversion(var: impl AsRef<str>, item: &Type, generics: &[TypeGeneric]) -> String {
+    let var = var.as_ref();
+
+    if item.kind.is_number() || item.kind == TypeKind::Bool {
+        let cast = match item.kind {
+            TypeKind::Bool => ".map(|it| it == 1)",
+            TypeKind::Char => ".map(|it| char::from_u32(it as u32).unwrap_or_default())",
+            TypeKind::U8 => ".map(|it| it as u8)",
+            TypeKind::U16 => ".map(|it| it as u16)",
+            TypeKind::U32 => ".map(|it| it as u32)",
+            TypeKind::U64 => ".map(|it| it as u64)",
+            _ => "",
+        };
+
+        return format!(
+            "    let {var} = primitive_array_to_vec(&mut env, &{var}).into_iter(){cast}.collect::<Vec<_>>();"
+        );
+    }
+
+    let convert = match &item.kind {
+        TypeKind::String => "Ok(String::from(env.get_string(it.into()).unwrap()))".into(),
+        TypeKind::Other(_) => {
+            let j = wrapper_prefix(item, generics);
+            let convert = if_else!(j.is_empty(), "clone", "to_rust");
+
+            format!(
+                "Ok((&*(object_pointer(env, it) as *mut {j}{})).{convert}())",
+                item.full_type()
+            )
+        }
+        kind if kind.is_std_object() => format!("{}(env, it)", kind.from_jobject_func().unwrap()),
+        kind => format!("Ok({}(env, it))", kind.from_jobject_func().unwrap()),
+    };
+
+    format!(
+        "    let {var} = match object_array_to_vec(&mut env, &{var}, |env, it| {convert}) {{ Ok(it) => it, Err(_) => return std::mem::zeroed() }};"
+    )
+}
+
+/// Get the prefix for the wrapper struct of a non-primitive type. Class
+/// generics aren't wrapped, everything else is `__JNI_{type}`.
+pub fn wrapper_prefix(ty: &Type, generics: &[TypeGeneric]) -> &'static str {
+    if_else!(
+        generics.iter().any(|v| v.name == ty.kind.rust_name()),
+        "",
+        "__JNI_"
+    )
+}
+
+/// Generate an expression converting a variable into a `jobject` (using `env: &mut JNIEnv`).
+/// Primitives are boxed, and non-primitive values are boxed as a pointer to
+/// their wrapper struct.
+pub fn jobject_conversion(var: impl AsRef<str>, ty: &Type, generics: &[TypeGeneric]) -> String {
+    let var = var.as_ref();
+
+    match &ty.kind {
+        TypeKind::Void => "JObject::null().as_raw()".into(),
+        TypeKind::String => format!("env.new_string({var}).unwrap().into_raw()"),
+        TypeKind::I8 | TypeKind::U8 => format!("jvalue_to_jobject(env, JValue::Byte({var} as i8))"),
+        TypeKind::I16 | TypeKind::U16 => {
+            format!("jvalue_to_jobject(env, JValue::Short({var} as i16))")
+        }
+        TypeKind::I32 | TypeKind::U32 => {
+            format!("jvalue_to_jobject(env, JValue::Int({var} as i32))")
+        }
+        TypeKind::I64 | TypeKind::U64 => {
+            format!("jvalue_to_jobject(env, JValue::Long({var} as i64))")
+        }
+        TypeKind::F32 => format!("jvalue_to_jobject(env, JValue::Float({var}))"),
+        TypeKind::F64 => format!("jvalue_to_jobject(env, JValue::Double({var}))"),
+        TypeKind::Bool => format!("jvalue_to_jobject(env, JValue::Bool({var} as u8))"),
+        TypeKind::Char => format!("jvalue_to_jobject(env, JValue::Char({var} as u16))"),
+        TypeKind::Duration
+        | TypeKind::SystemTime
+        | TypeKind::PathBuf
+        | TypeKind::Tuple(_)
+        | TypeKind::Value(_) => ty.kind.to_jobject_call("env", var).unwrap(),
+        TypeKind::Iter => format!(
+            "jvalue_to_jobject(env, JValue::Long({}))",
+            iterator_conversion(var, ty, generics)
+        ),
+        TypeKind::Slice(_) => unreachable!("slices are only arguments"),
+        TypeKind::Other(_) => format!(
+            "jvalue_to_jobject(env, JValue::Long(Box::leak(Box::new({var})) as *mut {}{} as jlong))",
+            wrapper_prefix(ty, generics),
+            ty.full_type()
+        ),
+    }
+}
+
+/// Generate an expression converting an iterator variable into a
+/// `NativeIterator` pointer.
+pub fn iterator_conversion(var: impl AsRef<str>, ty: &Type, generics: &[TypeGeneric]) -> String {
+    format!(
+        "iterator_to_jlong({}, |env, val| {})",
+        var.as_ref(),
+        jobject_conversion("val", &ty.item_type(), generics)
+    )
+}
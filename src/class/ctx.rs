@@ -2,7 +2,7 @@
 
 use crate::if_else;
 
-use super::{Class, generic::TypeGeneric};
+use super::{Class, generic::TypeGeneric, threading::Threading, ty::Type};
 
 /// A codegen context for classes
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -18,6 +18,18 @@ pub struct ClassCtx {
 
     /// Is the class a wrapper?
     pub wrapped: bool,
+
+    /// Is the class a value class?
+    pub value: bool,
+
+    /// Are natives instrumented?
+    pub instrument: bool,
+
+    /// Are the objects made for Java tracked?
+    pub track_leaks: bool,
+
+    /// How objects can be used from different threads.
+    pub threading: Threading,
 }
 
 impl ClassCtx {
@@ -27,21 +39,192 @@ impl ClassCtx {
             name: class.name.clone(),
             package: class.package.clone(),
             generics: class.generics.clone(),
-            wrapped: class.wrapped,
+            wrapped: class.wrapped || class.value,
+            value: class.value,
+            instrument: false,
+            track_leaks: false,
+            threading: class.threading,
+        }
+    }
+
+    /// Get the Java name of the class, like `com.example.MyStruct`.
+    pub fn java_name(&self) -> String {
+        if_else!(
+            self.package.is_empty(),
+            self.name.clone(),
+            format!("{}.{}", self.package, self.name)
+        )
+    }
+
+    /// Get the lines starting an instrumented native, which enter a `tracing`
+    /// span and time the call. Primitive arguments are recorded as they are,
+    /// objects by their type. Empty if natives aren't instrumented.
+    pub fn instrument_code(&self, method: impl AsRef<str>, args: &[(&str, &Type)]) -> Vec<String> {
+        if !self.instrument {
+            return Vec::new();
+        }
+
+        const PRIMITIVES: &[&str] = &[
+            "jbyte", "jshort", "jint", "jlong", "jfloat", "jdouble", "jboolean", "jchar",
+        ];
+
+        let class = self.java_name();
+        let method = method.as_ref();
+        let mut fields = vec![
+            format!("class = \"{class}\""),
+            format!("method = \"{method}\""),
+        ];
+
+        for (name, ty) in args {
+            let by_value =
+                ty.kind.is_primitive() && PRIMITIVES.contains(&ty.kind.jni_arg_name().as_str());
+
+            if by_value {
+                fields.push(format!("{name} = {name}"));
+            } else {
+                fields.push(format!("{name} = \"{}\"", ty.full_type()));
+            }
         }
+
+        vec![
+            format!("static __STAT: NativeStat = NativeStat::new(\"{class}\", \"{method}\");"),
+            format!(
+                "let __span = rs4j::tracing::trace_span!(\"native\", {}).entered();",
+                fields.join(", ")
+            ),
+            "let __timer = __STAT.start();".into(),
+        ]
+    }
+
+    /// Get [`Self::instrument_code`] as indented lines, for the start of a
+    /// native's body.
+    pub fn instrument_block(&self, method: impl AsRef<str>, args: &[(&str, &Type)]) -> String {
+        self.instrument_code(method, args)
+            .iter()
+            .map(|it| format!("    {it}\n"))
+            .collect()
+    }
+
+    /// Get an expression boxing a value for Java, as a `*mut ty`. If objects
+    /// are tracked, it's registered with `class`.
+    pub fn leak(&self, value: &str, ty: &str, class: &str) -> String {
+        let ptr = format!("Box::leak(Box::new({value})) as *mut {ty}");
+
+        if_else!(
+            self.track_leaks,
+            format!("track_alloc({ptr}, \"{class}\")"),
+            ptr
+        )
+    }
+
+    /// Get a statement unregistering a pointer before it's freed, if objects
+    /// are tracked.
+    pub fn unleak(&self, ptr: &str) -> String {
+        if_else!(
+            self.track_leaks,
+            format!("track_free({ptr} as *const ()); "),
+            String::new()
+        )
+    }
+
+    /// Get the lines guarding the object at `ptr`, for the start of a native
+    /// that borrows it. `exclusive` natives change the object (or free it).
+    /// Empty unless the class has a [`Threading`] modifier.
+    pub fn guard_code(&self, exclusive: bool) -> Vec<String> {
+        if !self.threading.is_guarded() || self.value {
+            return Vec::new();
+        }
+
+        let class = self.java_name();
+        let mut lines = vec![format!(
+            "let Some(__guard) = object_guard(&mut env, ptr, \"{class}\") else {{ return std::mem::zeroed(); }};"
+        )];
+
+        if self.threading == Threading::Local {
+            lines.push(format!(
+                "if !__guard.check_owner(&mut env, \"{class}\") {{ return std::mem::zeroed(); }}"
+            ));
+        }
+
+        lines.push(if_else!(
+            self.threading != Threading::Send && !exclusive,
+            "let __lock = __guard.read();".into(),
+            "let mut __lock = __guard.write();".into()
+        ));
+
+        lines.push(format!(
+            "if !check_not_freed(&mut env, *__lock, \"{class}\") {{ return std::mem::zeroed(); }}"
+        ));
+
+        lines
+    }
+
+    /// Get [`Self::guard_code`] as indented lines, for the start of a native's
+    /// body.
+    pub fn guard_block(&self, exclusive: bool) -> String {
+        self.guard_code(exclusive)
+            .iter()
+            .map(|it| format!("    {it}\n"))
+            .collect()
+    }
+
+    /// Get a statement marking the object at `ptr` as freed and dropping its
+    /// guard before it's freed, if it has one. Needs [`Self::guard_code`]'s
+    /// write lock.
+    pub fn unguard(&self) -> String {
+        if_else!(
+            self.threading.is_guarded() && !self.value,
+            "*__lock = true; drop_object_guard(ptr); ".into(),
+            String::new()
+        )
+    }
+
+    /// Get the code checking the Rust type implements the traits its
+    /// [`Threading`] modifier needs, if there are any.
+    pub fn threading_assertion(&self) -> Option<String> {
+        let bounds = self.threading.bounds()?;
+
+        let generics = self
+            .generics
+            .iter()
+            .map(|v| v.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Some(format!(
+            "const _: () = {{
+    fn assert<__T: ?Sized + {bounds}>() {{}}
+
+    #[allow(dead_code)]
+    fn check<{generics}>() {{
+        assert::<{}>();
+    }}
+}};",
+            self.raw_name_generics()
+        ))
     }
 
     /// The base name for Rust methods
     pub fn base_name(&self) -> String {
-        format!("{}_{}", self.package.replace(".", "_"), &self.name)
+        jni_mangle(self.java_class())
+    }
+
+    /// Get the JNI name of the Java class, like `com/example/MyStruct`.
+    pub fn java_class(&self) -> String {
+        if_else!(
+            self.package.is_empty(),
+            self.name.clone(),
+            format!("{}/{}", self.package.replace(".", "/"), &self.name)
+        )
     }
 
     /// Make a method name
     pub fn method_name(&self, method: impl AsRef<str>) -> String {
         format!(
-            "{}_{}",
+            "{}{}_{}",
             self.base_name(),
-            method.as_ref()
+            if_else!(self.value, "_00024Native", ""),
+            jni_mangle(method)
         )
     }
 
@@ -145,3 +328,26 @@ impl ClassCtx {
         if_else!(generics != "", format!("<{}>", generics), "".into())
     }
 }
+
+/// Mangle a name the way the JVM does when it looks up a native, turning
+/// `com/example/My_Struct` into `com_example_My_1Struct`.
+fn jni_mangle(name: impl AsRef<str>) -> String {
+    let mut out = String::new();
+
+    for c in name.as_ref().chars() {
+        match c {
+            '/' => out.push('_'),
+            '_' => out.push_str("_1"),
+            ';' => out.push_str("_2"),
+            '[' => out.push_str("_3"),
+            c if c.is_ascii_alphanumeric() => out.push(c),
+            c => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    out.push_str(&format!("_0{unit:04x}"));
+                }
+            }
+        }
+    }
+
+    out
+}
@@ -0,0 +1,267 @@
+//! Derive markers, bridging Rust traits to Java's object methods.
+
+use super::{base::RUST_BRIDGE_HEAD, ctx::ClassCtx};
+use crate::{
+    codegen::{
+        cx::Generator,
+        java::{JCall, JExpr, JExternMethod, JMember, JMethodImpl, JType},
+    },
+    if_else,
+};
+use std::collections::BTreeMap;
+
+/// A derive marker on a class, like `class Foo : Eq + Hash`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Derive {
+    /// `PartialEq` or `Eq`, giving `equals`.
+    Eq,
+
+    /// `Hash`, giving `hashCode`.
+    Hash,
+
+    /// `Display`, giving `toString`.
+    Display,
+
+    /// `Debug`, giving `toString` if there's no [`Derive::Display`].
+    Debug,
+
+    /// `PartialOrd`, giving `compareTo`. Incomparable values are equal.
+    PartialOrd,
+
+    /// `Ord`, giving `compareTo`.
+    Ord,
+
+    /// `Clone`, giving `copy`.
+    Clone,
+}
+
+impl Derive {
+    /// Get a [`Derive`] from its trait name.
+    pub fn from_name(name: impl AsRef<str>) -> Option<Self> {
+        match name.as_ref() {
+            "PartialEq" | "Eq" => Some(Self::Eq),
+            "Hash" => Some(Self::Hash),
+            "Display" => Some(Self::Display),
+            "Debug" => Some(Self::Debug),
+            "PartialOrd" => Some(Self::PartialOrd),
+            "Ord" => Some(Self::Ord),
+            "Clone" => Some(Self::Clone),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the native method.
+    pub fn native_name(&self) -> &'static str {
+        match self {
+            Self::Eq => "jni_equals",
+            Self::Hash => "jni_hash_code",
+            Self::Display | Self::Debug => "jni_to_string",
+            Self::PartialOrd | Self::Ord => "jni_compare_to",
+            Self::Clone => "jni_copy",
+        }
+    }
+
+    /// Does this make the class implement `Comparable`?
+    pub fn is_ord(&self) -> bool {
+        matches!(self, Self::PartialOrd | Self::Ord)
+    }
+
+    /// Get the return type of the native method.
+    fn ret(&self) -> JType {
+        match self {
+            Self::Eq => JType::Bool,
+            Self::Hash | Self::PartialOrd | Self::Ord => JType::Int,
+            Self::Display | Self::Debug => JType::String,
+            Self::Clone => JType::Long,
+        }
+    }
+
+    /// Does the native method take another object?
+    fn takes_other(&self) -> bool {
+        matches!(self, Self::Eq | Self::PartialOrd | Self::Ord)
+    }
+
+    /// Generate the native method code for Java.
+    pub fn native_java_code(&self) -> JMember {
+        let mut args = vec![("ptr".into(), JType::Long)];
+
+        if self.takes_other() {
+            args.push(("other".into(), JType::Long));
+        }
+
+        JMember::ExternMethod(JExternMethod {
+            name: self.native_name().into(),
+            private: true,
+            is_static: true,
+            ret: self.ret(),
+            args,
+        })
+    }
+
+    /// Generate the wrapper method code for Java.
+    pub fn wrapper_java_code(&self, cx: &ClassCtx, gcx: &Generator) -> JMember {
+        let native = self.native_name();
+        let class = JType::Custom(cx.raw_name_generics());
+
+        let (name, ret, args, value) = match self {
+            Self::Eq => {
+                let wild = if_else!(gcx.kotlin, "*", "?");
+
+                let generics = cx
+                    .generics
+                    .iter()
+                    .map(|_| wild)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let generics = if_else!(generics.is_empty(), generics, format!("<{generics}>"));
+                let class = format!("{}{generics}", cx.name);
+
+                let check = if_else!(
+                    gcx.kotlin,
+                    format!("other is {class} && {native}(__ptr, other.__ptr)"),
+                    format!("other instanceof {class} that && {native}(__ptr, that.__ptr)")
+                );
+
+                let any = JType::Custom(if_else!(gcx.kotlin, "Any", "Object").into());
+
+                (
+                    "equals",
+                    JType::Bool,
+                    vec![("other".into(), JType::Nullable(Box::new(any)))],
+                    JExpr::Name(check),
+                )
+            }
+
+            Self::PartialOrd | Self::Ord => (
+                "compareTo",
+                JType::Int,
+                vec![("other".into(), class.clone())],
+                JExpr::Call(JCall {
+                    target: native.into(),
+                    args: vec![
+                        JExpr::Name("__ptr".into()),
+                        JExpr::Name("other.__ptr".into()),
+                    ],
+                }),
+            ),
+
+            Self::Hash | Self::Display | Self::Debug => (
+                if_else!(*self == Self::Hash, "hashCode", "toString"),
+                self.ret(),
+                Vec::new(),
+                JExpr::Call(JCall {
+                    target: native.into(),
+                    args: vec![JExpr::Name("__ptr".into())],
+                }),
+            ),
+
+            Self::Clone => (
+                "copy",
+                class.clone(),
+                Vec::new(),
+                JExpr::Call(JCall {
+                    target: "from".into(),
+                    args: vec![JExpr::Call(JCall {
+                        target: native.into(),
+                        args: vec![JExpr::Name("__ptr".into())],
+                    })],
+                }),
+            ),
+        };
+
+        JMember::MethodImpl(JMethodImpl {
+            name: name.into(),
+            ret,
+            args,
+            is_static: false,
+            private: false,
+            is_override: *self != Self::Clone,
+            is_suspend: false,
+            is_operator: false,
+            generics: BTreeMap::new(),
+            code: vec![JExpr::Return(Box::new(value))],
+        })
+    }
+
+    /// Generate the native method code for Rust.
+    pub fn native_rust_code(&self, cx: &ClassCtx) -> String {
+        let method = cx.method_name(self.native_name());
+        let class = cx.name();
+        let trace = cx.instrument_block(&self.native_name()[4..], &[]);
+        let guard = cx.guard_block(false);
+        let class_c = cx.name_generics();
+
+        let generics = cx
+            .generics
+            .iter()
+            .map(|v| v.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let copy = cx.leak(
+            &format!("{class}::of(it.to_rust().clone())"),
+            &class_c,
+            &cx.java_name(),
+        );
+        let copy = format!("({copy}) as jlong");
+
+        let (args, ret, body) = match self {
+            Self::Eq => (
+                ", other: jlong",
+                "jboolean",
+                "(it.to_rust() == other.to_rust()) as jboolean",
+            ),
+
+            Self::Hash => (
+                "",
+                "jint",
+                "let mut hasher = ::std::hash::DefaultHasher::new();
+    ::std::hash::Hash::hash(&it.to_rust(), &mut hasher);
+
+    let hash = ::std::hash::Hasher::finish(&hasher);
+
+    (hash ^ (hash >> 32)) as jint",
+            ),
+
+            Self::Display | Self::Debug => (
+                "",
+                "jstring",
+                if_else!(
+                    *self == Self::Display,
+                    "env.new_string(it.to_rust().to_string()).unwrap().as_raw()",
+                    "env.new_string(format!(\"{:?}\", it.to_rust())).unwrap().as_raw()"
+                ),
+            ),
+
+            Self::PartialOrd => (
+                ", other: jlong",
+                "jint",
+                "it.to_rust().partial_cmp(&other.to_rust()).map_or(0, |it| it as jint)",
+            ),
+
+            Self::Ord => (
+                ", other: jlong",
+                "jint",
+                "it.to_rust().cmp(&other.to_rust()) as jint",
+            ),
+
+            Self::Clone => ("", "jlong", copy.as_str()),
+        };
+
+        let other = if_else!(
+            self.takes_other(),
+            format!("\n    let other = &*(other as *mut {class_c});"),
+            String::new()
+        );
+
+        format!(
+            "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{method}<'local, {generics}>(mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong{args}) -> {ret} {{
+{trace}{guard}    let it = &*(ptr as *mut {class_c});{other}
+
+    {body}
+}}"
+        )
+    }
+}
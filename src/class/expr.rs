@@ -1,6 +1,22 @@
 //! An [`Expr`].
 
-use super::{field::Field, generic::TypeGeneric, method::Method};
+use super::{
+    Class, constant::Constant, external::ExternClass, field::Field, generic::TypeGeneric,
+    imp::TraitImpl, method::Method, property::Property, static_field::StaticField,
+};
+
+/// A top-level item.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Item {
+    /// A [`Class`].
+    Class(Class),
+
+    /// A [`TraitImpl`].
+    Impl(TraitImpl),
+
+    /// An [`ExternClass`].
+    Extern(ExternClass),
+}
 
 /// An expression.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -14,6 +30,18 @@ pub enum Expr {
     /// A [`Field`].
     Field(Field),
 
+    /// A [`Constant`].
+    Const(Constant),
+
+    /// A [`StaticField`].
+    StaticField(StaticField),
+
+    /// A [`Property`].
+    Property(Property),
+
+    /// A [`TraitImpl`].
+    Impl(TraitImpl),
+
     /// Nothing.
     None,
 }
@@ -69,6 +97,42 @@ impl Expr {
         }
     }
 
+    /// Get this as a trait impl.
+    pub fn get_impl(&self) -> Option<TraitImpl> {
+        if let Self::Impl(i) = self {
+            Some(i.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get this as a constant.
+    pub fn get_const(&self) -> Option<Constant> {
+        if let Self::Const(c) = self {
+            Some(c.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get this as a static field.
+    pub fn get_static_field(&self) -> Option<StaticField> {
+        if let Self::StaticField(f) = self {
+            Some(f.clone())
+        } else {
+            None
+        }
+    }
+
+    /// Get this as a property.
+    pub fn get_property(&self) -> Option<Property> {
+        if let Self::Property(p) = self {
+            Some(p.clone())
+        } else {
+            None
+        }
+    }
+
     /// Get this as a field.
     pub fn get_field(&self) -> Option<Field> {
         if let Self::Field(f) = self {
@@ -0,0 +1,358 @@
+//! Extern classes, which are Java classes called from Rust.
+
+use anyhow::{Result, bail};
+
+use super::{
+    base::RUST_BRIDGE_HEAD_MANGLE,
+    method::Method,
+    ty::{Type, TypeKind},
+};
+use crate::{codegen::java::JType, if_else};
+
+/// A Java class declared with `extern class java.util.ArrayList { ... }`.
+/// It doesn't generate any Java code, just a Rust struct holding a
+/// `GlobalRef` to an object of the class, with a method calling each of the
+/// declared ones.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ExternClass {
+    /// The class name.
+    pub name: String,
+
+    /// The class's package.
+    pub package: String,
+
+    /// The name of the Rust struct, if it's not the class name.
+    pub rust_name: Option<String>,
+
+    /// The methods to generate.
+    pub methods: Vec<Method>,
+}
+
+impl ExternClass {
+    /// Get the name of the Rust struct, which `.rs4j` files use for this class.
+    /// Nested classes, like `Map$Entry`, become `MapEntry`.
+    pub fn rust_name(&self) -> String {
+        self.rust_name
+            .clone()
+            .unwrap_or_else(|| self.name.replace("$", ""))
+    }
+
+    /// Get the JNI name of the class, like `java/util/ArrayList`.
+    pub fn java_class(&self) -> String {
+        if_else!(
+            self.package.is_empty(),
+            self.name.clone(),
+            format!("{}/{}", self.package.replace(".", "/"), self.name)
+        )
+    }
+
+    /// Check that a method can be called on an extern class.
+    pub fn check_method(method: &Method) -> Result<(), &'static str> {
+        if method.is_mut || method.is_consumed || method.boxed || method.is_async {
+            return Err("`static`, `init` or `optional` methods in an extern class");
+        }
+
+        if method.custom_name.is_some() || method.object.is_some() {
+            return Err("a `#[java_name]` for an extern class method");
+        }
+
+        if !method.generics.is_empty() {
+            return Err("extern class methods without type parameters");
+        }
+
+        if method
+            .args
+            .iter()
+            .any(|it| it.default.is_some() || it.varargs)
+        {
+            return Err("extern class methods without default values or varargs");
+        }
+
+        let types = method.args.iter().map(|it| &it.ty).chain([&method.ret]);
+
+        for ty in types {
+            if ty.generics.is_some() || !Self::is_supported(&ty.kind) {
+                return Err("Java primitives, `String` or classes in an extern class");
+            }
+        }
+
+        if method.is_optional && (method.is_init || Self::primitive(&method.ret.kind).is_some()) {
+            return Err("`optional` only on methods returning objects");
+        }
+
+        Ok(())
+    }
+
+    /// Can extern classes use this type?
+    fn is_supported(kind: &TypeKind) -> bool {
+        matches!(kind, TypeKind::Void | TypeKind::String | TypeKind::Other(_))
+            || Self::primitive(kind).is_some()
+    }
+
+    /// Get the `JValue` variant, JNI `Primitive`, `JValueOwned` getter and Java
+    /// type of a primitive.
+    fn primitive(
+        kind: &TypeKind,
+    ) -> Option<(&'static str, &'static str, &'static str, &'static str)> {
+        Some(match kind {
+            TypeKind::I8 | TypeKind::U8 => ("Byte", "Byte", "b", "i8"),
+            TypeKind::I16 | TypeKind::U16 => ("Short", "Short", "s", "i16"),
+            TypeKind::I32 | TypeKind::U32 => ("Int", "Int", "i", "i32"),
+            TypeKind::I64 | TypeKind::U64 => ("Long", "Long", "j", "i64"),
+            TypeKind::F32 => ("Float", "Float", "f", "f32"),
+            TypeKind::F64 => ("Double", "Double", "d", "f64"),
+            TypeKind::Bool => ("Bool", "Boolean", "z", "u8"),
+            TypeKind::Char => ("Char", "Char", "c", "u16"),
+            _ => return None,
+        })
+    }
+
+    /// Find the extern class a type refers to. `Object` is `java.lang.Object`.
+    fn find<'a>(&self, ty: &Type, externs: &'a [ExternClass]) -> Result<Option<&'a ExternClass>> {
+        let TypeKind::Other(name) = &ty.kind else {
+            return Ok(None);
+        };
+
+        if name == "Object" {
+            return Ok(None);
+        }
+
+        match externs.iter().find(|it| &it.rust_name() == name) {
+            Some(it) => Ok(Some(it)),
+
+            None => bail!(
+                "Extern class {} uses unknown class {}! Declare it with `extern class`, or use `Object`.",
+                self.name,
+                name
+            ),
+        }
+    }
+
+    /// Get the [`JType`] of a type, for its descriptor.
+    fn java_type(&self, ty: &Type, externs: &[ExternClass]) -> Result<JType> {
+        Ok(match self.find(ty, externs)? {
+            Some(it) => JType::Custom(it.java_class()),
+            None if ty.kind == TypeKind::Other("Object".into()) => {
+                JType::Custom("java.lang.Object".into())
+            }
+            None => ty.kind.j_type(),
+        })
+    }
+
+    /// Get the Rust type of an argument.
+    fn arg_type(&self, ty: &Type, externs: &[ExternClass]) -> Result<String> {
+        Ok(match (&ty.kind, self.find(ty, externs)?) {
+            (_, Some(it)) => format!("&{}", it.rust_name()),
+            (TypeKind::String, _) => "&str".into(),
+            (TypeKind::Other(_), _) => "&JObject".into(),
+            (kind, _) => kind.rust_name(),
+        })
+    }
+
+    /// Get the Rust type a method returns.
+    fn ret_type(&self, method: &Method, externs: &[ExternClass]) -> Result<String> {
+        let ty = match (&method.ret.kind, self.find(&method.ret, externs)?) {
+            _ if method.is_init => "Self".into(),
+            (_, Some(it)) => it.rust_name(),
+            (TypeKind::Other(_), _) => "GlobalRef".into(),
+            (kind, _) => kind.rust_name(),
+        };
+
+        Ok(if_else!(method.is_optional, format!("Option<{ty}>"), ty))
+    }
+
+    /// Generate an expression converting the returned `val` into its Rust type.
+    fn ret_conversion(&self, method: &Method, externs: &[ExternClass]) -> Result<String> {
+        let ty = &method.ret;
+
+        if let Some((_, _, getter, cast)) = Self::primitive(&ty.kind) {
+            return Ok(match ty.kind {
+                TypeKind::Bool => "val.z()?".into(),
+                TypeKind::Char => "char::from_u32(val.c()? as u32).unwrap_or_default()".into(),
+                _ if ty.kind.rust_name() == cast => format!("val.{getter}()?"),
+                _ => format!("val.{getter}()? as {}", ty.kind.rust_name()),
+            });
+        }
+
+        let convert = match (&ty.kind, self.find(ty, externs)?) {
+            (TypeKind::Void, _) => return Ok("val.v()?".into()),
+            (TypeKind::String, _) => "local_string(env, val)?".into(),
+            (_, Some(it)) => format!("{}(global_ref(env, val)?)", it.rust_name()),
+            _ => "global_ref(env, val)?".into(),
+        };
+
+        Ok(if_else!(
+            method.is_optional,
+            format!("if val.is_null() {{ None }} else {{ Some({convert}) }}"),
+            convert
+        ))
+    }
+
+    /// Generate the Rust code for one of the methods.
+    fn method_rust_code(&self, method: &Method, externs: &[ExternClass]) -> Result<String> {
+        let name = &method.name;
+        let java_name = if_else!(method.is_init, "<init>".into(), method.java_name());
+        let mut args = Vec::new();
+        let mut params = Vec::new();
+        let mut pre = Vec::new();
+        let mut sig = String::new();
+
+        if !method.is_static && !method.is_init {
+            args.push("&self".to_string());
+        }
+
+        args.push("env: &mut JNIEnv".into());
+
+        for arg in &method.args {
+            let var = &arg.name;
+
+            args.push(format!("{var}: {}", self.arg_type(&arg.ty, externs)?));
+            sig.push_str(&self.java_type(&arg.ty, externs)?.descriptor());
+
+            if let Some((variant, _, _, cast)) = Self::primitive(&arg.ty.kind) {
+                let val = if_else!(
+                    arg.ty.kind.rust_name() == cast,
+                    var.clone(),
+                    format!("{var} as {cast}")
+                );
+
+                params.push(format!("JValue::{variant}({val}).as_jni()"));
+            } else if arg.ty.kind == TypeKind::String {
+                pre.push(format!(
+                    "let {var} = env.auto_local(env.new_string({var})?);"
+                ));
+                params.push(format!("JValue::Object(&{var}).as_jni()"));
+            } else if self.find(&arg.ty, externs)?.is_some() {
+                params.push(format!("JValue::Object({var}.0.as_obj()).as_jni()"));
+            } else {
+                params.push(format!("JValue::Object({var}).as_jni()"));
+            }
+        }
+
+        let ret_java = if_else!(
+            method.is_init,
+            JType::Void,
+            self.java_type(&method.ret, externs)?
+        );
+
+        let sig = format!("({sig}){}", ret_java.descriptor());
+        let ret = self.ret_type(method, externs)?;
+        let params = params.join(", ");
+
+        let ret_kind = match Self::primitive(&method.ret.kind) {
+            _ if method.is_init => "".into(),
+            Some((_, primitive, _, _)) => format!("ReturnType::Primitive(Primitive::{primitive})"),
+            None if method.ret.kind == TypeKind::Void => {
+                "ReturnType::Primitive(Primitive::Void)".into()
+            }
+            None => "ReturnType::Object".into(),
+        };
+
+        let (id, call) = if method.is_init {
+            (
+                "CachedMethod",
+                format!(
+                    "env.new_object_unchecked(<&JClass>::from(class.as_obj()), method, &[{params}])"
+                ),
+            )
+        } else if method.is_static {
+            (
+                "CachedStaticMethod",
+                format!(
+                    "env.call_static_method_unchecked(<&JClass>::from(class.as_obj()), method, {ret_kind}, &[{params}])"
+                ),
+            )
+        } else {
+            (
+                "CachedMethod",
+                format!("env.call_method_unchecked(&self.0, method, {ret_kind}, &[{params}])"),
+            )
+        };
+
+        let convert = if_else!(
+            method.is_init,
+            "Self(global_ref(env, val)?)".into(),
+            self.ret_conversion(method, externs)?
+        );
+
+        // Objects come back as a `JValueOwned`, so they're taken out of it first.
+        let convert = if_else!(
+            !method.is_init && matches!(method.ret.kind, TypeKind::String | TypeKind::Other(_)),
+            format!("{{\n            let val = val.l()?;\n            {convert}\n        }}"),
+            convert
+        );
+
+        // Getting a primitive out of the value already gives a `Result`.
+        let convert = match convert.strip_suffix("?") {
+            Some(it) if it.starts_with("val.") && !it.contains(' ') => it.into(),
+            _ => format!("Ok({convert})"),
+        };
+
+        let pre = pre
+            .iter()
+            .map(|it| format!("\n        {it}"))
+            .collect::<String>();
+
+        Ok(format!(
+            "    {RUST_BRIDGE_HEAD_MANGLE}
+    pub fn {name}({args}) -> errors::Result<{ret}> {{
+        static METHOD: {id} = {id}::new(\"{java_name}\", \"{sig}\");
+
+        let class = Self::class(env)?;
+        let method = METHOD.get(env, &class)?;{pre}
+        let val = unsafe {{ {call} }}?;
+
+        {convert}
+    }}",
+            args = args.join(", ")
+        ))
+    }
+
+    /// Generate the Rust code for this class.
+    pub fn rust_code(&self, externs: &[ExternClass]) -> Result<String> {
+        let name = self.rust_name();
+        let mut methods = Vec::new();
+
+        for (i, method) in self.methods.iter().enumerate() {
+            if self.methods[..i].iter().any(|it| it.name == method.name) {
+                bail!(
+                    "Method {} of extern class {} is declared twice! Give overloads their own names, with `#[java_name]`.",
+                    method.name,
+                    self.name
+                );
+            }
+
+            methods.push(self.method_rust_code(method, externs)?);
+        }
+
+        let methods = methods
+            .iter()
+            .map(|it| format!("\n\n{it}"))
+            .collect::<String>();
+
+        Ok(format!(
+            "/// The Java class `{}`.
+#[derive(Clone)]
+pub struct {name}(pub GlobalRef);
+
+impl {name} {{
+    {RUST_BRIDGE_HEAD_MANGLE}
+    pub const JAVA_CLASS: &str = \"{}\";
+
+    {RUST_BRIDGE_HEAD_MANGLE}
+    pub fn class(env: &mut JNIEnv) -> errors::Result<GlobalRef> {{
+        static CLASS: CachedClass = CachedClass::new({name}::JAVA_CLASS);
+
+        CLASS.get(env)
+    }}
+
+    {RUST_BRIDGE_HEAD_MANGLE}
+    pub fn of(env: &mut JNIEnv, obj: &JObject) -> errors::Result<Self> {{
+        Ok(Self(env.new_global_ref(obj)?))
+    }}{methods}
+}}",
+            self.java_class().replace("/", "."),
+            self.java_class()
+        ))
+    }
+}
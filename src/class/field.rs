@@ -17,6 +17,32 @@ use super::{
     ty::{Type, TypeKind},
 };
 
+/// Which accessors a field has in Java.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub enum Access {
+    /// A getter and a setter.
+    #[default]
+    ReadWrite,
+
+    /// Only a getter, with `readonly field`.
+    ReadOnly,
+
+    /// Only a setter, with `writeonly field`.
+    WriteOnly,
+}
+
+impl Access {
+    /// Does this have a getter?
+    pub fn can_read(&self) -> bool {
+        *self != Self::WriteOnly
+    }
+
+    /// Does this have a setter?
+    pub fn can_write(&self) -> bool {
+        *self != Self::ReadOnly
+    }
+}
+
 /// A field in a class.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Field {
@@ -28,6 +54,9 @@ pub struct Field {
 
     /// Is this field rust-only?
     pub rust: bool,
+
+    /// Which accessors this has in Java.
+    pub access: Access,
 }
 
 impl Field {
@@ -37,6 +66,7 @@ impl Field {
             name: name.as_ref().into(),
             ty,
             rust: false,
+            access: Access::ReadWrite,
         }
     }
 
@@ -74,6 +104,8 @@ impl Field {
                 name,
                 private: false,
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: false,
                 ret: JType::Void,
                 args: vec![("value".into(), self.ty.j_type())],
@@ -101,6 +133,8 @@ impl Field {
                 name,
                 private: false,
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: false,
                 ret: JType::Void,
                 args: vec![("value".into(), self.ty.j_type())],
@@ -194,6 +228,8 @@ impl Field {
         let name = cx.method_name(format!("jni_set_{}", &self.name));
         let class = cx.name_generics();
         let field = &self.name;
+        let trace = cx.instrument_block(format!("set_{field}"), &[("val", &self.ty)]);
+        let guard = cx.guard_block(true);
 
         let generics = cx
             .generics
@@ -213,7 +249,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     ptr: jlong,
     val: {val_ty},
 ) -> jlong {{
-    let it = &mut *(ptr as *mut {class});
+{trace}{guard}    let it = &mut *(ptr as *mut {class});
 
     it.{field} = val;
 
@@ -229,11 +265,28 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     ptr: jlong,
     val: JString<'local>,
 ) -> jlong {{
-    let it = &mut *(ptr as *mut {class});
+{trace}{guard}    let it = &mut *(ptr as *mut {class});
     let val = env.get_string(&val).unwrap().to_str().unwrap().to_string();
 
     it.{field} = val;
 
+    ptr as jlong
+}}"
+            )
+        } else if let Some(convert) = self.ty.kind.from_jobject_call("&mut env", "&val") {
+            format!(
+                "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+    val: JObject<'local>,
+) -> jlong {{
+{trace}{guard}    let it = &mut *(ptr as *mut {class});
+    let val = {convert};
+
+    it.{field} = val;
+
     ptr as jlong
 }}"
             )
@@ -248,7 +301,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     ptr: jlong,
     val: jlong,
 ) -> jlong {{
-    let it = &mut *(ptr as *mut {class});
+{trace}{guard}    let it = &mut *(ptr as *mut {class});
 
     it.{field} = val as *mut {other_name};
 
@@ -263,6 +316,8 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
         let name = cx.method_name(format!("jni_get_{}", &self.name));
         let class = cx.name_generics();
         let field = &self.name;
+        let trace = cx.instrument_block(format!("get_{field}"), &[]);
+        let guard = cx.guard_block(false);
         let ret = self.ty.kind.jni_name();
 
         let generics = cx
@@ -280,7 +335,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     class: JClass<'local>,
     ptr: jlong,
 ) -> {ret} {{
-    let it = &*(ptr as *mut {class});
+{trace}{guard}    let it = &*(ptr as *mut {class});
 
     it.{field} as {ret}
 }}"
@@ -293,8 +348,24 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     class: JClass<'local>,
     ptr: jlong,
 ) -> jstring {{
-    let it = &*(ptr as *mut {class});
+{trace}{guard}    let it = &*(ptr as *mut {class});
     env.new_string(it.{field}.clone()).unwrap().as_raw()
+}}"
+            )
+        } else if let Some(convert) = self
+            .ty
+            .kind
+            .to_jobject_call("&mut env", &format!("it.{field}.clone()"))
+        {
+            format!(
+                "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+) -> jobject {{
+{trace}{guard}    let it = &*(ptr as *mut {class});
+    {convert}
 }}"
             )
         } else {
@@ -305,7 +376,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
     class: JClass<'local>,
     ptr: jlong,
 ) -> jlong {{
-    let it = &mut *(ptr as *mut {class});
+{trace}{guard}    let it = &*(ptr as *mut {class});
 
     it.{field} as jlong
 }}"
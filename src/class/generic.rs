@@ -43,3 +43,21 @@ impl From<Type> for TypeGeneric {
         }
     }
 }
+
+/// A method's type parameter, like `T in [i32, String]` or `T: Shape`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MethodGeneric {
+    /// The generic name.
+    pub name: String,
+
+    /// A list of bounds. Once resolved, an erased one only keeps the traits
+    /// that have a Java interface.
+    pub bounds: Vec<Type>,
+
+    /// The types the method is monomorphized over. An erased one gets the
+    /// classes implementing its bounds.
+    pub types: Vec<Type>,
+
+    /// Is it erased to its bounds in Java, instead of a list of types?
+    pub erased: bool,
+}
@@ -0,0 +1,146 @@
+//! Trait `impl`s on classes.
+//!
+//! An `impl Trait for Class;` maps an operator trait to its Java method, and an
+//! `impl Trait for Class { fn ...; }` block routes every method in it through
+//! the trait, generating a Java interface for it.
+
+use anyhow::{Result, bail};
+
+use super::{
+    arg::FunctionArg,
+    generic::TypeGeneric,
+    method::Method,
+    ops::Operator,
+    ty::{Type, TypeKind},
+};
+use crate::if_else;
+
+/// An `impl Trait for Class` item.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TraitImpl {
+    /// The trait, with its generics.
+    pub tr: Type,
+
+    /// The name of the class it's implemented for.
+    pub target: String,
+
+    /// The output type, if it's not the class itself.
+    pub output: Option<Type>,
+
+    /// The methods in an `impl` block.
+    pub methods: Option<Vec<Method>>,
+}
+
+impl TraitImpl {
+    /// Get the name of the trait (and its Java interface).
+    pub fn name(&self) -> String {
+        self.tr.kind.rust_name()
+    }
+
+    /// Get the path to the trait, for calling its methods.
+    pub fn path(&self) -> String {
+        match &self.tr.generics {
+            Some(_) => format!("{}::<{}>", self.name(), self.tr.get_generics()),
+            None => self.name(),
+        }
+    }
+
+    /// Get the generics of the trait's Java interface. These are the trait's
+    /// generics that are the class's own [`TypeGeneric`]s, anything else is
+    /// filled in.
+    pub fn interface_generics(&self, generics: &[TypeGeneric]) -> Vec<String> {
+        self.tr
+            .generics
+            .iter()
+            .flatten()
+            .map(|it| it.kind.rust_name())
+            .filter(|it| generics.iter().any(|g| g.name == *it))
+            .collect()
+    }
+
+    /// Get the Java interface type the class implements.
+    pub fn interface_type(&self, generics: &[TypeGeneric]) -> String {
+        let generics = self.interface_generics(generics);
+
+        if_else!(
+            generics.is_empty(),
+            self.name(),
+            format!("{}<{}>", self.name(), generics.join(", "))
+        )
+    }
+
+    /// Get the methods this adds to its class.
+    pub fn methods(&self) -> Result<Vec<Method>> {
+        if let Some(methods) = &self.methods {
+            return Ok(methods
+                .iter()
+                .map(|it| Method {
+                    object: Some(self.path()),
+                    interface: Some(self.name()),
+                    ..it.clone()
+                })
+                .collect());
+        }
+
+        let name = self.name();
+
+        let Some(op) = Operator::from_name(&name) else {
+            bail!("Unknown operator trait {name} for {}!", self.target);
+        };
+
+        let target = Type::new(TypeKind::Other(self.target.clone()), None);
+        let rhs = self.tr.generics.as_ref().and_then(|it| it.first().cloned());
+
+        // Every impl needs its own natives, so `Mul<f32>` and `Mul<Vec3>` can
+        // both be `times` in Java.
+        let (method, object) = match &rhs {
+            Some(rhs) => (
+                format!("{}_{}", op.rust_name(), rhs.kind.tuple_id().to_lowercase()),
+                format!("::std::ops::{name}::<{}>", rhs.full_type()),
+            ),
+
+            None => (op.rust_name().into(), format!("::std::ops::{name}")),
+        };
+
+        let args = op
+            .arg_name()
+            .map(|arg| FunctionArg {
+                name: arg.into(),
+                ty: rhs.unwrap_or(target.clone()),
+                borrow: false,
+                mutable: false,
+                into: false,
+                default: None,
+                varargs: false,
+            })
+            .into_iter()
+            .collect();
+
+        let ret = if_else!(
+            op.is_assign(),
+            Type::default(),
+            self.output.clone().unwrap_or(target)
+        );
+
+        Ok(vec![Method {
+            name: method,
+            args,
+            ret,
+            is_mut: op.is_assign(),
+            is_static: false,
+            is_init: false,
+            is_optional: false,
+            is_consumed: false,
+            object: Some(object),
+            custom_name: Some(op.rust_name().into()),
+            java_name: Some(op.java_name().into()),
+            operator: Some(op),
+            interface: None,
+            inherited: None,
+            generics: Vec::new(),
+            instance: Vec::new(),
+            boxed: false,
+            is_async: false,
+        }])
+    }
+}
@@ -0,0 +1,68 @@
+//! Inheriting a base class through `Deref` or `AsRef`.
+
+use convert_case::{Case, Casing};
+
+use super::{
+    method::Method,
+    ty::{Type, TypeKind},
+};
+
+/// How a class gets to its base.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Via {
+    /// `Deref`, and `DerefMut` for `mut` methods.
+    Deref,
+
+    /// `AsRef`, and `AsMut` for `mut` methods.
+    AsRef,
+}
+
+/// A base class, like `class Derived : Base via Deref`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Inherit {
+    /// The name of the base class.
+    pub base: String,
+
+    /// How to get to the base.
+    pub via: Via,
+}
+
+impl Inherit {
+    /// Get the base from an object, for calling one of its methods.
+    pub fn receiver(&self, it: impl AsRef<str>, is_mut: bool) -> String {
+        let base = &self.base;
+        let it = it.as_ref();
+
+        match (self.via, is_mut) {
+            (Via::Deref, false) => format!("::std::ops::Deref::deref({it})"),
+            (Via::Deref, true) => format!("::std::ops::DerefMut::deref_mut({it})"),
+            (Via::AsRef, false) => format!("::std::convert::AsRef::<{base}>::as_ref({it})"),
+            (Via::AsRef, true) => format!("::std::convert::AsMut::<{base}>::as_mut({it})"),
+        }
+    }
+
+    /// Get the method copying the base out of an object, so it can be passed
+    /// where the base class is expected.
+    pub fn to_base(&self) -> Method {
+        Method {
+            name: format!("to_{}", self.base.to_case(Case::Snake)),
+            args: Vec::new(),
+            ret: Type::new(TypeKind::Other(self.base.clone()), None),
+            is_mut: false,
+            is_static: false,
+            is_init: false,
+            is_optional: false,
+            is_consumed: false,
+            object: Some("::std::clone::Clone".into()),
+            custom_name: Some("clone".into()),
+            java_name: None,
+            operator: None,
+            interface: None,
+            inherited: Some(self.clone()),
+            generics: Vec::new(),
+            instance: Vec::new(),
+            boxed: false,
+            is_async: false,
+        }
+    }
+}
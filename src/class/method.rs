@@ -1,6 +1,11 @@
 //! The module for [`Method`]s.
 
-use super::{arg::FunctionArg, ty::Type};
+use super::{
+    arg::FunctionArg, conv::wrapper_prefix, ctx::ClassCtx, generic::MethodGeneric,
+    inherit::Inherit, ops::Operator, ty::Type,
+};
+use crate::{codegen::java::JType, if_else};
+use convert_case::{Case, Casing};
 
 /// A method.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -35,16 +40,109 @@ pub struct Method {
     /// Is there a custom name?
     pub custom_name: Option<String>,
 
+    /// Is there a different name for Java?
+    pub java_name: Option<String>,
+
+    /// The operator trait this comes from, if any.
+    pub operator: Option<Operator>,
+
+    /// The Java interface this implements, if it comes from a trait `impl` block.
+    pub interface: Option<String>,
+
+    /// The base class this is inherited from, if any.
+    pub inherited: Option<Inherit>,
+
+    /// The method's own type parameters. Only an erased one is left once
+    /// they're resolved, making this a Java dispatcher for its instances.
+    pub generics: Vec<MethodGeneric>,
+
+    /// The types this instance of a generic method is for, in order.
+    pub instance: Vec<Type>,
+
     /// Does it need to be boxed?
     pub boxed: bool,
+
+    /// Is this backed by a Rust future? If so, Java gets a `CompletableFuture`
+    /// and Kotlin gets a `suspend fun`.
+    pub is_async: bool,
 }
 
 impl Method {
     /// Get the name of the native method this calls.
     pub fn calls(&self) -> String {
-        format!(
-            "jni_{}",
-            self.custom_name.clone().unwrap_or(self.name.clone())
+        format!("jni_{}", self.name)
+    }
+
+    /// Get the path of the Rust function this calls, after its class.
+    pub fn rust_path(&self) -> String {
+        let name = self.custom_name.clone().unwrap_or(self.name.clone());
+
+        if self.instance.is_empty() {
+            return name;
+        }
+
+        let types = self
+            .instance
+            .iter()
+            .map(|it| it.full_type())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        format!("{name}::<{types}>")
+    }
+
+    /// Does this have its own native method? Generic dispatchers only call
+    /// their instances.
+    pub fn has_native(&self) -> bool {
+        self.generics.is_empty()
+    }
+
+    /// Does the native return the Java object itself? Generated classes are
+    /// created on the Rust side, but class generics are still passed as pointers.
+    pub fn returns_object(&self, cx: &ClassCtx) -> bool {
+        !self.ret.kind.is_primitive() && !wrapper_prefix(&self.ret, &cx.generics).is_empty()
+    }
+
+    /// Get the Java return type. Optional objects can be `null`.
+    pub fn java_ret(&self, cx: &ClassCtx) -> JType {
+        if_else!(
+            self.is_optional && self.returns_object(cx),
+            JType::Nullable(Box::new(self.ret.j_type())),
+            self.ret.j_type()
         )
     }
+
+    /// Get the name of this method in Java.
+    pub fn java_name(&self) -> String {
+        self.java_name
+            .clone()
+            .unwrap_or_else(|| self.name.to_case(Case::Camel))
+    }
+
+    /// Get the name of the native method this calls from Java. Value classes
+    /// keep their natives in a nested `Native` class, since records can't
+    /// have native methods.
+    pub fn native_target(&self, cx: &ClassCtx) -> String {
+        if_else!(cx.value, format!("Native.{}", self.calls()), self.calls())
+    }
+
+    /// Get the receiver for calling this on an object, borrowing it unless
+    /// it's taken by value. Inherited methods go through the base class.
+    pub fn receiver(&self, it: impl AsRef<str>, by_value: bool) -> String {
+        let it = it.as_ref();
+
+        let Some(inherited) = &self.inherited else {
+            return match (by_value, self.is_mut) {
+                (true, _) => it.into(),
+                (false, true) => format!("&mut {it}"),
+                (false, false) => format!("&{it}"),
+            };
+        };
+
+        match (by_value, self.is_mut) {
+            (true, _) => format!("{}.clone()", inherited.receiver(format!("&{it}"), false)),
+            (false, true) => inherited.receiver(format!("&mut {it}"), true),
+            (false, false) => inherited.receiver(format!("&{it}"), false),
+        }
+    }
 }
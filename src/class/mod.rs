@@ -1,36 +1,409 @@
 //! The module for [`Class`]es.
 
-use base::{free_method_java, free_method_java_wrapper, free_method_rust, of_func};
+use anyhow::{Result, bail};
+use base::{
+    free_method_java, free_method_java_wrapper, free_method_rust, guard_call_java,
+    guard_method_java, guard_method_rust, of_func,
+};
+use constant::Constant;
 use convert_case::{Case, Casing};
 use ctx::ClassCtx;
-use field::Field;
+use derive::Derive;
+use field::{Access, Field};
 use generic::TypeGeneric;
+use imp::TraitImpl;
+use inherit::Inherit;
 use method::Method;
+use property::Property;
+use static_field::StaticField;
 use std::collections::BTreeMap;
+use threading::Threading;
+use ty::TypeKind;
 
 use crate::{
     class::{base::RUST_BRIDGE_HEAD_MANGLE, ty::Type},
     codegen::{
         cx::Generator,
         java::{
-            JCall, JClassDef, JCtor, JExpr, JField, JGetterImpl, JGetterSetterImpl, JIf, JMember, JMethodImpl, JNewCall, JSetField, JType
+            JCall, JClassDef, JCtor, JExpr, JExternMethod, JField, JGetterImpl, JGetterSetterImpl,
+            JIf, JMember, JMethodImpl, JNested, JNewCall, JSetField, JType,
         },
+        record::value_components,
     },
     if_else,
 };
 
 pub mod arg;
 pub mod base;
+pub mod constant;
 pub mod conv;
 pub mod ctx;
+pub mod derive;
 pub mod expr;
+pub mod external;
 pub mod field;
 pub mod generic;
+pub mod imp;
+pub mod inherit;
 pub mod method;
 pub mod native;
+pub mod ops;
+pub mod property;
+pub mod static_field;
+pub mod threading;
 pub mod ty;
 pub mod wrapper;
 
+/// Add the methods from trait `impl`s to the classes they're for. Top-level
+/// `impl`s are moved into their class first.
+pub fn resolve_impls(classes: &mut [Class], impls: &[TraitImpl]) -> Result<()> {
+    for imp in impls {
+        let Some(class) = classes.iter_mut().find(|it| it.name == imp.target) else {
+            bail!(
+                "Can't find class {} to implement {} for!",
+                imp.target,
+                imp.tr.full_type()
+            );
+        };
+
+        class.impls.push(imp.clone());
+    }
+
+    for class in classes {
+        for imp in &class.impls {
+            if imp.target != class.name {
+                bail!(
+                    "Can't implement {} for {} inside of class {}!",
+                    imp.tr.full_type(),
+                    imp.target,
+                    class.name
+                );
+            }
+
+            class.methods.extend(imp.methods()?);
+        }
+    }
+
+    Ok(())
+}
+
+/// Expand methods with type parameters into an instance for each of their
+/// types, each with its own native. Java sees the instances as overloads. An
+/// erased type parameter is expanded over the classes implementing its bounds,
+/// and the generic method is kept to pick the right instance at runtime.
+pub fn resolve_generics(classes: &mut [Class]) -> Result<()> {
+    let all = classes.to_vec();
+
+    // Only traits with an `impl` block have a Java interface. Inheriting one
+    // from a base doesn't count, since Rust only sees the base implement it.
+    let implements = |class: &Class, tr: &Type| {
+        class
+            .impls
+            .iter()
+            .any(|it| it.methods.is_some() && it.name() == tr.kind.rust_name())
+    };
+
+    for class in classes.iter_mut() {
+        let mut methods = Vec::new();
+
+        for mut method in std::mem::take(&mut class.methods) {
+            if method.generics.is_empty() {
+                methods.push(method);
+                continue;
+            }
+
+            let erased = method.generics.iter().any(|it| it.erased);
+
+            if erased && method.generics.len() > 1 {
+                bail!(
+                    "Method {} of {} can only have one type parameter if it's erased!",
+                    method.name,
+                    class.name
+                );
+            }
+
+            if erased {
+                let generic = &mut method.generics[0];
+
+                generic
+                    .bounds
+                    .retain(|tr| all.iter().any(|it| implements(it, tr)));
+
+                if generic.bounds.is_empty() {
+                    bail!(
+                        "Type parameter {} of {} in {} needs a list of types, or a trait with an `impl` block as a bound!",
+                        generic.name,
+                        method.name,
+                        class.name
+                    );
+                }
+
+                generic.types = all
+                    .iter()
+                    .filter(|it| generic.bounds.iter().all(|tr| implements(it, tr)))
+                    .map(|it| Type::new(TypeKind::Other(it.name.clone()), None))
+                    .collect();
+
+                let generic = TypeKind::Other(generic.name.clone());
+
+                if !method.args.iter().any(|it| it.ty.kind == generic) {
+                    bail!(
+                        "Method {} of {} needs an argument of its erased type parameter!",
+                        method.name,
+                        class.name
+                    );
+                }
+
+                if method.ret.kind != generic && method.ret.contains(&generic.rust_name()) {
+                    bail!(
+                        "Method {} of {} can only return its erased type parameter as it is!",
+                        method.name,
+                        class.name
+                    );
+                }
+            }
+
+            let mut instances = vec![Vec::<Type>::new()];
+
+            for generic in &method.generics {
+                instances = instances
+                    .iter()
+                    .flat_map(|prev| {
+                        generic.types.iter().map(move |ty| {
+                            let mut next = prev.clone();
+
+                            next.push(ty.clone());
+                            next
+                        })
+                    })
+                    .collect();
+            }
+
+            for types in instances {
+                let mut instance = Method {
+                    name: format!(
+                        "{}_{}",
+                        method.name,
+                        types
+                            .iter()
+                            .map(|it| it.kind.tuple_id().to_lowercase())
+                            .collect::<Vec<_>>()
+                            .join("_")
+                    ),
+                    custom_name: Some(method.custom_name.clone().unwrap_or(method.name.clone())),
+                    java_name: Some(method.java_name()),
+                    generics: Vec::new(),
+                    instance: types.clone(),
+                    ..method.clone()
+                };
+
+                for (generic, ty) in method.generics.iter().zip(&types) {
+                    instance.ret = instance.ret.substitute(&generic.name, ty);
+
+                    for arg in &mut instance.args {
+                        arg.ty = arg.ty.substitute(&generic.name, ty);
+                    }
+                }
+
+                methods.push(instance);
+            }
+
+            if erased {
+                methods.push(method);
+            }
+        }
+
+        class.methods = methods;
+    }
+
+    Ok(())
+}
+
+/// Give methods sharing a name distinct native names, so they can be Java
+/// overloads. Each one is renamed after its argument types, keeping its Java
+/// and Rust names. Overloads Java can't tell apart, including the ones for
+/// default values, are an error.
+pub fn resolve_overloads(classes: &mut [Class]) -> Result<()> {
+    for class in classes {
+        let names = class
+            .methods
+            .iter()
+            .map(|it| it.name.clone())
+            .collect::<Vec<_>>();
+
+        for method in &mut class.methods {
+            if names.iter().filter(|it| **it == method.name).count() < 2 {
+                continue;
+            }
+
+            let types = method
+                .args
+                .iter()
+                .map(|it| it.ty.kind.tuple_id().to_lowercase())
+                .collect::<Vec<_>>();
+
+            method.java_name = Some(method.java_name());
+            method.custom_name = Some(method.custom_name.clone().unwrap_or(method.name.clone()));
+
+            if !types.is_empty() {
+                method.name = format!("{}_{}", method.name, types.join("_"));
+            }
+        }
+
+        let mut signatures = Vec::new();
+
+        for method in &class.methods {
+            let name = if_else!(method.is_init, "<init>".into(), method.java_name());
+
+            let first = method
+                .args
+                .iter()
+                .position(|it| it.default.is_some())
+                .unwrap_or(method.args.len());
+
+            for given in first..=method.args.len() {
+                let args = method.args[..given]
+                    .iter()
+                    .map(|it| format!("{:?}", it.ty.j_type()))
+                    .collect::<Vec<_>>();
+
+                let signature = (name.clone(), args);
+
+                if signatures.contains(&signature) {
+                    bail!(
+                        "Method {} of {} has an overload with the same arguments in Java!",
+                        method.name,
+                        class.name
+                    );
+                }
+
+                signatures.push(signature);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the methods of base classes to the classes inheriting them. These go
+/// through `Deref` or `AsRef` on every call, so they always see the current
+/// base. Only the base's own methods are inherited, not the ones it inherits.
+pub fn resolve_bases(classes: &mut [Class]) -> Result<()> {
+    let bases = classes.to_vec();
+
+    for class in classes {
+        let Some(inherits) = class.inherits.clone() else {
+            continue;
+        };
+
+        let Some(base) = bases.iter().find(|it| it.name == inherits.base) else {
+            bail!("Can't find base class {} of {}!", inherits.base, class.name);
+        };
+
+        if class.value || base.value {
+            bail!(
+                "Class {} can't inherit {}, value classes have no pointer to share!",
+                class.name,
+                base.name
+            );
+        }
+
+        if !base.generics.is_empty() {
+            bail!(
+                "Base class {} of {} can't have generics!",
+                base.name,
+                class.name
+            );
+        }
+
+        for method in &base.methods {
+            if method.is_static
+                || method.is_init
+                || method.inherited.is_some()
+                || class.methods.iter().any(|it| it.name == method.name)
+            {
+                continue;
+            }
+
+            class.methods.push(Method {
+                inherited: Some(inherits.clone()),
+                ..method.clone()
+            });
+        }
+
+        class.methods.push(inherits.to_base());
+
+        let impls = base.impls.iter().filter(|it| it.methods.is_some());
+
+        class.impls.extend(impls.cloned());
+    }
+
+    Ok(())
+}
+
+/// Resolve uses of value classes across all the classes, so they're copied
+/// instead of wrapped. Value classes can't have generics, and their methods
+/// can't change or consume them, since Java only ever has a copy.
+pub fn resolve_values(classes: &mut [Class]) -> Result<()> {
+    let values = classes
+        .iter()
+        .filter(|it| it.value)
+        .map(|it| it.name.clone())
+        .collect::<Vec<_>>();
+
+    for class in classes.iter().filter(|it| it.value) {
+        if !class.generics.is_empty() {
+            bail!("Value class {} can't have generics!", class.name);
+        }
+
+        if !class.derives.is_empty() {
+            bail!(
+                "Value class {} can't have derive markers, records already have them!",
+                class.name
+            );
+        }
+
+        if !class.properties.is_empty()
+            || class.fields.iter().any(|it| it.access != Access::ReadWrite)
+        {
+            bail!(
+                "Value class {} can't have properties or read-only and write-only fields, records are copies!",
+                class.name
+            );
+        }
+
+        for method in &class.methods {
+            if method.is_init || method.is_mut || method.is_consumed {
+                bail!(
+                    "Method {} of value class {} can't be `init`, `mut` or `consumed`!",
+                    method.name,
+                    class.name
+                );
+            }
+        }
+    }
+
+    for class in classes {
+        for field in &mut class.fields {
+            field.ty.resolve_values(&values);
+        }
+
+        for field in &mut class.statics {
+            field.ty.resolve_values(&values);
+        }
+
+        for method in &mut class.methods {
+            method.ret.resolve_values(&values);
+
+            for arg in &mut method.args {
+                arg.ty.resolve_values(&values);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// A Java class.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Class {
@@ -46,6 +419,15 @@ pub struct Class {
     /// A list of fields.
     pub fields: Vec<Field>,
 
+    /// A list of constants.
+    pub constants: Vec<Constant>,
+
+    /// A list of static fields.
+    pub statics: Vec<StaticField>,
+
+    /// A list of properties, backed by Rust methods.
+    pub properties: Vec<Property>,
+
     /// A list of methods.
     pub methods: Vec<Method>,
 
@@ -55,6 +437,21 @@ pub struct Class {
     /// Should it be a wrapper?
     pub wrapped: bool,
 
+    /// Is this a value class, copied into a record instead of wrapping a pointer?
+    pub value: bool,
+
+    /// How its objects can be used from different threads.
+    pub threading: Threading,
+
+    /// Derive markers, bridging Rust traits to `equals`, `hashCode` and friends.
+    pub derives: Vec<Derive>,
+
+    /// Trait `impl`s, from the class body or the top level.
+    pub impls: Vec<TraitImpl>,
+
+    /// The base class, inherited through `Deref` or `AsRef`.
+    pub inherits: Option<Inherit>,
+
     pub real_name: Option<(String, Vec<Type>)>,
 }
 
@@ -66,9 +463,17 @@ impl Class {
             package: package.as_ref().into(),
             imports: Self::default_imports(),
             fields: Vec::new(),
+            constants: Vec::new(),
+            statics: Vec::new(),
+            properties: Vec::new(),
             methods: Vec::new(),
             generics: Vec::new(),
             wrapped: false,
+            value: false,
+            threading: Threading::Unchecked,
+            derives: Vec::new(),
+            impls: Vec::new(),
+            inherits: None,
             real_name: None,
         }
     }
@@ -101,12 +506,55 @@ impl Class {
         self
     }
 
+    /// Add a constant.
+    pub fn constant(mut self, constant: Constant) -> Self {
+        self.constants.push(constant);
+        self
+    }
+
+    /// Add a static field.
+    pub fn static_field(mut self, field: StaticField) -> Self {
+        self.statics.push(field);
+        self
+    }
+
+    /// Add a property.
+    pub fn property(mut self, property: Property) -> Self {
+        self.properties.push(property);
+        self
+    }
+
     /// Add a native method.
     pub fn method(mut self, method: Method) -> Self {
         self.methods.push(method);
         self
     }
 
+    /// Add a derive marker.
+    pub fn derive(mut self, derive: Derive) -> Self {
+        self.derives.push(derive);
+        self
+    }
+
+    /// Get the derive markers that generate code. `Display` wins over `Debug`
+    /// for `toString`, and `Ord` wins over `PartialOrd` for `compareTo`.
+    pub fn object_derives(&self) -> Vec<Derive> {
+        let mut derives = self.derives.clone();
+
+        derives.sort();
+        derives.dedup();
+
+        if derives.contains(&Derive::Display) {
+            derives.retain(|it| *it != Derive::Debug);
+        }
+
+        if derives.contains(&Derive::Ord) {
+            derives.retain(|it| *it != Derive::PartialOrd);
+        }
+
+        derives
+    }
+
     /// Create the Java code.
     pub fn java_code(&self, gcx: &Generator) -> JClassDef {
         let pkg = &self.package;
@@ -120,6 +568,10 @@ impl Class {
         let wheres = cx.kotlin_wheres();
         let class_ge = cx.raw_name_generics();
 
+        if self.value {
+            return self.value_java_code(gcx);
+        }
+
         let generics = self
             .generics
             .iter()
@@ -140,37 +592,49 @@ impl Class {
             })
             .collect::<BTreeMap<_, _>>();
 
-        for func in &self.methods {
-            natives.push(func.native_java_code());
+        for func in self.methods.iter().filter(|it| it.has_native()) {
+            natives.push(func.native_java_code(&cx));
         }
 
         for func in &self.methods {
-            wrappers.push(func.wrapper_java_code(&cx));
+            wrappers.push(func.wrapper_java_code(&cx, gcx));
+            wrappers.extend(func.overloads_java_code(&cx, gcx));
         }
 
-        for field in &self.fields {
-            if field.rust {
-                continue;
-            }
+        // Properties look just like fields to Java.
+        let java_fields = self
+            .fields
+            .iter()
+            .filter(|it| !it.rust)
+            .cloned()
+            .chain(self.properties.iter().map(|it| it.field()));
 
+        for field in java_fields {
             let name = &field.name;
 
-            natives.push(field.java_setter());
-            natives.push(field.java_getter());
+            if field.access.can_write() {
+                natives.push(field.java_setter());
+            }
 
-            fields.push(JMember::GetterSetter(JGetterSetterImpl {
-                name: field.name.clone(),
-                setter_name: format!("set_{}", &field.name).to_case(Case::Camel),
-                getter_name: format!("get_{}", &field.name).to_case(Case::Camel),
-                is_static: false,
-                private: false,
-                ty: field.ty.clone(),
-            }));
+            if field.access.can_read() {
+                natives.push(field.java_getter());
+            }
+
+            fields.push(match field.access {
+                Access::ReadWrite => JMember::GetterSetter(JGetterSetterImpl {
+                    name: field.name.clone(),
+                    setter_name: format!("set_{}", &field.name).to_case(Case::Camel),
+                    getter_name: format!("get_{}", &field.name).to_case(Case::Camel),
+                    is_static: false,
+                    private: false,
+                    ty: field.ty.clone(),
+                }),
 
-            // fields.push(field.java_setter_wrapper());
-            // fields.push(field.java_getter_wrapper());
+                Access::ReadOnly => field.java_getter_wrapper(),
+                Access::WriteOnly => field.java_setter_wrapper(),
+            });
 
-            if !field.is_primitive() {
+            if !field.is_primitive() && field.access.can_write() {
                 update_fields.push(JExpr::If(JIf {
                     cond: Box::new(JExpr::Name(format!("field == \"{name}\""))),
                     body: vec![JExpr::SetField(JSetField {
@@ -184,10 +648,26 @@ impl Class {
             }
         }
 
+        for field in &self.statics {
+            natives.extend(field.native_java_code());
+            wrappers.extend(field.wrapper_java_code(&cx));
+        }
+
+        for derive in self.object_derives() {
+            natives.push(derive.native_java_code());
+            wrappers.push(derive.wrapper_java_code(&cx, gcx));
+        }
+
         natives.push(free_method_java());
         wrappers.push(free_method_java_wrapper());
 
-        let vars = vec![
+        let mut vars = self
+            .constants
+            .iter()
+            .map(|it| it.java_code(gcx))
+            .collect::<Vec<_>>();
+
+        vars.extend([
             JMember::Field(JField {
                 name: "__ptr".into(),
                 is_final: false,
@@ -212,9 +692,9 @@ impl Class {
                 ty: JType::Nullable(Box::new(JType::String)),
                 value: Some("null".into()),
             }),
-        ];
+        ]);
 
-        let inits = vec![
+        let mut inits = vec![
             JMember::Ctor(JCtor {
                 name: class.clone(),
                 args: vec![("ptr".into(), JType::Long)],
@@ -249,6 +729,17 @@ impl Class {
             }),
         ];
 
+        // Guarded objects register their guard whenever Java wraps a pointer.
+        if cx.threading.is_guarded() {
+            natives.push(guard_method_java());
+
+            for member in wrappers.iter_mut().chain(&mut inits) {
+                if let JMember::Ctor(ctor) = member {
+                    ctor.code.push(guard_call_java());
+                }
+            }
+        }
+
         let froms = vec![
             JMember::MethodImpl(JMethodImpl {
                 name: "from".into(),
@@ -256,6 +747,8 @@ impl Class {
                 ret: JType::Custom(class_ge.clone()), // TODO: Use proper generic types here
                 generics: generics.clone(),
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: true,
                 private: false,
                 code: vec![JExpr::Return(Box::new(JExpr::New(JNewCall {
@@ -273,6 +766,8 @@ impl Class {
                 ret: JType::Custom(class_ge.clone()), // TODO: Use proper generic types here
                 generics: generics.clone(),
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: true,
                 private: false,
                 code: vec![JExpr::Return(Box::new(JExpr::New(JNewCall {
@@ -301,6 +796,8 @@ impl Class {
             JMember::MethodImpl(JMethodImpl {
                 name: "updateField".into(),
                 is_override: true,
+                is_suspend: false,
+                is_operator: false,
                 is_static: false,
                 private: false,
                 ret: JType::Void,
@@ -323,44 +820,185 @@ impl Class {
         members.extend(froms);
         members.extend(overrides);
 
+        let mut extends = vec!["ParentClass".into(), "NativeClass".into()];
+
+        if self.derives.iter().any(|it| it.is_ord()) {
+            extends.push(format!("Comparable<{class_ge}>"));
+        }
+
+        extends.extend(self.interfaces());
+
         JClassDef {
             pkg: pkg.into(),
             name: class_g,
-            extends: vec!["ParentClass".into(), "NativeClass".into()],
+            extends,
             members,
-            imports: self.imports.clone(),
+            imports: self.java_imports(gcx),
             wheres,
+            record: None,
         }
     }
 
-    /// Generate rust bindgen code
-    pub fn rust_code(&self) -> String {
+    /// Create the Java code for a value class. This is a record holding copies
+    /// of the fields, with no pointer behind it.
+    pub fn value_java_code(&self, gcx: &Generator) -> JClassDef {
         let cx = self.new_context();
+        let mut members = Vec::new();
+        let mut natives = Vec::new();
+
+        let own_natives = self
+            .methods
+            .iter()
+            .filter(|it| it.has_native())
+            .map(|it| it.native_java_code(&cx))
+            .chain(self.statics.iter().flat_map(|it| it.native_java_code()));
+
+        for native in own_natives {
+            natives.push(match native {
+                JMember::ExternMethod(it) => JMember::ExternMethod(JExternMethod {
+                    private: false,
+                    ..it
+                }),
+
+                it => it,
+            });
+        }
+
+        for constant in &self.constants {
+            members.push(constant.java_code(gcx));
+        }
+
+        // Records can't have native methods, so they live in a nested class.
+        if !natives.is_empty() {
+            members.push(JMember::Nested(JNested {
+                name: "Native".into(),
+                members: natives,
+            }));
+        }
+
+        for func in &self.methods {
+            members.push(func.wrapper_java_code(&cx, gcx));
+            members.extend(func.overloads_java_code(&cx, gcx));
+        }
+
+        for field in &self.statics {
+            members.extend(field.wrapper_java_code(&cx));
+        }
+
+        JClassDef {
+            pkg: self.package.clone(),
+            name: self.name.clone(),
+            extends: self.interfaces(),
+            members,
+            imports: self.java_imports(gcx),
+            wheres: String::new(),
+            record: Some(value_components(self)),
+        }
+    }
+
+    /// Get the Java interfaces this implements, for its trait `impl` blocks.
+    pub fn interfaces(&self) -> Vec<String> {
+        self.impls
+            .iter()
+            .filter(|it| it.methods.is_some())
+            .map(|it| it.interface_type(&self.generics))
+            .collect()
+    }
+
+    /// Get the imports for the Java code.
+    pub fn java_imports(&self, gcx: &Generator) -> Vec<String> {
+        let mut imports = self.imports.clone();
+
+        if self.methods.iter().any(|it| it.is_async) {
+            imports.push("java.util.concurrent.CompletableFuture".into());
+
+            if gcx.kotlin {
+                imports.push("kotlinx.coroutines.future.await".into());
+            }
+        }
+
+        if self.methods.iter().any(|it| it.ret.kind == TypeKind::Iter) {
+            imports.push("org.stardustmodding.rs4j.util.NativeIterator".into());
+        }
+
+        imports
+    }
+
+    /// Generate rust bindgen code
+    pub fn rust_code(&self, gcx: &Generator) -> String {
+        let mut cx = self.new_context();
+
+        cx.instrument = gcx.instrument;
+        cx.track_leaks = gcx.track_leaks;
+
         let mut code = Vec::new();
 
+        code.extend(cx.threading_assertion());
+
+        if self.value {
+            for m in self.methods.iter().filter(|it| it.has_native()) {
+                code.push(m.native_rust_code(&cx, &Vec::new(), &self.generics));
+            }
+
+            for f in &self.statics {
+                code.extend(f.native_rust_code(&cx));
+            }
+
+            return format!("{}\n{}", self.create_wrapper(gcx), code.join("\n\n"));
+        }
+
         for f in &self.fields {
             if f.rust {
                 continue;
             }
 
-            code.push(f.rust_setter(&cx));
-            code.push(f.rust_getter(&cx));
+            if f.access.can_write() {
+                code.push(f.rust_setter(&cx));
+            }
+
+            if f.access.can_read() {
+                code.push(f.rust_getter(&cx));
+            }
+        }
+
+        for p in &self.properties {
+            code.extend(p.rust_setter(&cx));
+            code.push(p.rust_getter(&cx));
         }
 
-        for m in &self.methods {
+        for m in self.methods.iter().filter(|it| it.has_native()) {
             code.push(m.native_rust_code(&cx, &self.fields, &self.generics));
         }
 
+        for f in &self.statics {
+            code.extend(f.native_rust_code(&cx));
+        }
+
+        for derive in self.object_derives() {
+            code.push(derive.native_rust_code(&cx));
+        }
+
+        if cx.threading.is_guarded() {
+            code.push(guard_method_rust(&cx));
+        }
+
         code.push(free_method_rust(&cx, &self.fields));
 
-        format!("{}\n{}", self.create_wrapper(), code.join("\n\n"))
+        format!("{}\n{}", self.create_wrapper(gcx), code.join("\n\n"))
     }
 
     /// Create the Rust code for a wrapper struct.
-    pub fn create_wrapper(&self) -> String {
-        let cx = self.new_context();
+    pub fn create_wrapper(&self, gcx: &Generator) -> String {
+        let mut cx = self.new_context();
+
+        cx.instrument = gcx.instrument;
+        cx.track_leaks = gcx.track_leaks;
+
         let mut fields = Vec::new();
 
+        // Value classes only hold the value itself.
+        let own_fields = if_else!(self.value, Vec::new(), self.fields.clone());
+
         let generics = self
             .generics
             .iter()
@@ -379,11 +1017,11 @@ impl Class {
 
         let generics_nb = if_else!(generics_nb != "", format!("<{}>", generics_nb), "".into());
 
-        if self.wrapped {
+        if cx.wrapped {
             fields.push(format!("    pub __inner: {},", &cx.raw_name_generics()));
         }
 
-        for field in &self.fields {
+        for field in &own_fields {
             if field.is_primitive() {
                 fields.push(format!("    pub {}: {},", field.name, field.ty.full_type()));
             } else {
@@ -405,7 +1043,7 @@ impl Class {
         let mut impls = Vec::new();
         let mut convert = Vec::new();
 
-        for field in &self.fields {
+        for field in &own_fields {
             if field.is_primitive() {
                 convert.push(format!(
                     "            {}: self.{}.clone(),",
@@ -419,9 +1057,17 @@ impl Class {
             }
         }
 
-        impls.push(of_func(&cx, &self.fields));
+        impls.push(of_func(&cx, &own_fields));
 
-        if self.wrapped {
+        // Value classes are records, so they can't be created from a pointer.
+        if !self.value {
+            impls.push(format!(
+                "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub const JAVA_CLASS: &str = \"{}\";",
+                cx.java_class()
+            ));
+        }
+
+        if cx.wrapped {
             impls.push(format!(
                 "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn to_rust(&self) -> {}{generics_nb} {{\n        self.__inner.clone()\n    }}",
                 self.name,
@@ -435,7 +1081,11 @@ impl Class {
             ));
         }
 
-        for native in &self.methods {
+        if !self.value {
+            impls.push(self.set_func(&cx));
+        }
+
+        for native in self.methods.iter().filter(|it| it.has_native()) {
             impls.push(native.native_rust_wrapper_code(&cx));
         }
 
@@ -450,6 +1100,31 @@ impl Class {
         format!("{}\n\n{}", struct_, impl_)
     }
 
+    /// Create the `__set()` function, writing a value back into the wrapper
+    /// after a mutable method changed it.
+    fn set_func(&self, cx: &ClassCtx) -> String {
+        let class = &cx.raw_name_generics();
+        let mut setters = Vec::new();
+
+        if cx.wrapped {
+            setters.push("        self.__inner = base;".into());
+        } else {
+            for field in &self.fields {
+                if field.is_primitive() {
+                    setters.push(format!("        self.{0} = base.{0};", field.name));
+                } else {
+                    setters.push(format!("        *self.{0} = base.{0};", field.name));
+                }
+            }
+        }
+
+        let setters = setters.join("\n");
+
+        format!(
+            "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn __set(&mut self, base: {class}) {{\n{setters}\n    }}"
+        )
+    }
+
     /// Create a new [`ClassCtx`]
     fn new_context(&self) -> ClassCtx {
         ClassCtx::new(self)
@@ -4,7 +4,7 @@ use super::{ctx::ClassCtx, field::Field, generic::TypeGeneric, method::Method, t
 use crate::{
     class::{
         base::{RUST_BRIDGE_HEAD, RUST_BRIDGE_HEAD_MANGLE},
-        conv::conversion_method,
+        conv::{array_conversion, conversion_method, jobject_conversion},
     },
     codegen::java::{JExternMethod, JMember, JType},
     if_else,
@@ -12,18 +12,30 @@ use crate::{
 
 impl Method {
     /// Generate Java code for this method.
-    pub fn native_java_code(&self) -> JMember {
+    pub fn native_java_code(&self, cx: &ClassCtx) -> JMember {
         let name = if_else!(
             self.is_init,
             format!("jni_init_{}", self.name),
             format!("jni_{}", self.name)
         );
 
-        let ret = self.ret.kind.j_type();
+        // Class generics are still passed as pointers.
+        let ret = if self.returns_object(cx) {
+            self.java_ret(cx)
+        } else if self.ret.kind == TypeKind::Iter || !self.ret.kind.is_primitive() {
+            JType::Long
+        } else {
+            self.ret.kind.j_type()
+        };
         let mut args = Vec::new();
 
+        // Value classes pass themselves, everything else passes its pointer.
         if !self.is_static {
-            args.push(("ptr".into(), JType::Long));
+            if cx.value {
+                args.push(("__self".into(), JType::Custom(cx.name.clone())));
+            } else {
+                args.push(("ptr".into(), JType::Long));
+            }
         }
 
         for arg in &self.args {
@@ -34,15 +46,32 @@ impl Method {
             }
         }
 
+        if self.is_async {
+            args.push(("future".into(), self.native_future_type()));
+        }
+
         JMember::ExternMethod(JExternMethod {
             name,
             private: true,
-            ret: if_else!(self.is_init, JType::Long, ret),
+            ret: if_else!(self.is_init || self.is_async, JType::Long, ret),
             is_static: !self.is_init,
             args,
         })
     }
 
+    /// Get the type of the `CompletableFuture` an `async` native completes.
+    /// Non-primitive values are completed with their pointer.
+    pub fn native_future_type(&self) -> JType {
+        JType::Generic {
+            base: Box::new(JType::Custom("CompletableFuture".into())),
+            params: vec![JType::Boxed(Box::new(if_else!(
+                self.ret.kind.is_primitive(),
+                self.ret.kind.j_type(),
+                JType::Long
+            )))],
+        }
+    }
+
     /// Generate Rust code for this method.
     pub fn native_rust_code(
         &self,
@@ -72,7 +101,12 @@ impl Method {
         for arg in &self.args {
             args.push(format!("{}: {}", arg.name, arg.ty.kind.jni_arg_name()));
 
-            if arg.borrow && !arg.ty.kind.is_primitive() {
+            // Async tasks can't hold on to borrows, so their wrappers take ownership.
+            let by_ref = !arg.ty.kind.is_primitive()
+                || arg.ty.kind.from_jobject_func().is_some()
+                || matches!(arg.ty.kind, TypeKind::Slice(_));
+
+            if arg.borrow && by_ref && !self.is_async {
                 if arg.mutable {
                     args_nt.push(format!("&mut {}", arg.name.clone()));
                 } else {
@@ -93,36 +127,87 @@ impl Method {
             }
         }
 
+        if self.is_async {
+            args.push("future: JObject<'local>".into());
+        }
+
         let args = args.join(", ");
         let args_nt = args_nt.join(", ");
         let mut_ = if_else!(self.is_mut, "mut ", "");
 
-        let mut conversions = Vec::new();
+        let mut conversions = vec!["remember_java_vm(&env);".to_string()];
 
-        if !self.is_static {
+        let traced = self
+            .args
+            .iter()
+            .map(|it| (it.name.as_str(), &it.ty))
+            .collect::<Vec<_>>();
+
+        conversions.extend(cx.instrument_code(method, &traced));
+
+        if cx.value {
+            if !self.is_static {
+                conversions.push(format!(
+                    "let it = &{class_c}::of(__value_from_jobject_{}(&mut env, &__self));",
+                    cx.name
+                ));
+            }
+        } else if !self.is_static {
+            conversions.extend(cx.guard_code(self.is_mut || self.is_consumed));
             conversions.push(format!("let it = &{mut_}*(ptr as *mut {class_c});"));
         }
 
         for arg in &self.args {
-            if let Some(conv) = conversion_method(&arg.name, &arg.ty, arg.mutable) {
+            if let TypeKind::Slice(item) = &arg.ty.kind {
+                conversions.push(array_conversion(&arg.name, item, &cx.generics));
+            } else if let Some(conv) = conversion_method(&arg.name, &arg.ty, arg.mutable) {
                 conversions.push(conv);
             }
         }
 
         // Native methods are ALWAYS static
-        let base_args = if self.is_init {
+        let base_args = if cx.value {
+            if_else!(
+                self.is_static,
+                "mut env: JNIEnv<'local>, class: JClass<'local>",
+                "mut env: JNIEnv<'local>, class: JClass<'local>, __self: JObject<'local>"
+            )
+        } else if self.is_init {
             "mut env: JNIEnv<'local>, obj: JObject<'local>"
+        } else if self.is_static {
+            "mut env: JNIEnv<'local>, class: JClass<'local>"
         } else {
             "mut env: JNIEnv<'local>, class: JClass<'local>, ptr: jlong"
         };
 
-        let ret = self.ret.kind.jni_name();
+        let ret = if_else!(
+            self.returns_object(cx),
+            "jobject".into(),
+            self.ret.kind.jni_name()
+        );
 
         let mut post =
             if_else!(self.ret.kind == TypeKind::String, "env.new_string(", "").to_string();
         let mut post2 =
             if_else!(self.ret.kind == TypeKind::String, ").unwrap().as_raw()", "").to_string();
 
+        if let Some(func) = self.ret.kind.to_jobject_func() {
+            post = format!("{func}(&mut env, ");
+            post2 = if_else!(
+                self.ret.kind.is_std_object(),
+                ").unwrap_or(std::ptr::null_mut())",
+                ")"
+            )
+            .into();
+        }
+
+        if self.ret.kind == TypeKind::Iter {
+            let convert = jobject_conversion("val", &self.ret.item_type(), &cx.generics);
+
+            post = "iterator_to_jlong(".into();
+            post2 = format!(", |env, val| {convert})");
+        }
+
         let pre = conversions.join("\n");
 
         match self.ret.kind {
@@ -140,29 +225,79 @@ impl Method {
 
         if !self.ret.kind.is_primitive() {
             let rt = self.ret.full_type();
-            let j = if_else!(
-                cx.generics
-                    .iter()
-                    .find(|v| v.name == self.ret.kind.rust_name())
-                    .is_none(),
-                "__JNI_",
-                ""
-            );
+
+            // Generated classes get created here, class generics are left to Java.
+            let convert = if !self.returns_object(cx) {
+                let ptr = cx.leak("val", &rt, &format!("{}<{rt}>", cx.java_name()));
+
+                format!(";\n    ({ptr}) as jlong")
+            } else if cx.track_leaks {
+                let ptr = cx.leak("val", "_", &format!("{}.{rt}", cx.package));
+
+                format!(
+                    ";\n    pointer_to_jobject(&mut env, <__JNI_{rt}>::JAVA_CLASS, {ptr} as jlong)"
+                )
+            } else {
+                format!(";\n    object_to_jobject(&mut env, val, <__JNI_{rt}>::JAVA_CLASS)")
+            };
 
             if self.is_consumed {
                 cpost.push_str("let val = ");
-                cpost2.push_str(&format!(
-                    ";\n    (Box::leak(Box::new(val)) as *mut {j}{rt}) as jlong"
-                ));
+                cpost2.push_str(&convert);
             } else {
                 post.push_str("let val = ");
-                post2.push_str(&format!(
-                    ";\n    (Box::leak(Box::new(val)) as *mut {j}{rt}) as jlong"
+                post2.push_str(&convert);
+            }
+        }
+
+        if self.is_async {
+            let target = if_else!(self.is_static, format!("{class}::"), "it.".to_string());
+            let convert = jobject_conversion("val", &self.ret, &cx.generics);
+
+            let convert = if_else!(
+                self.is_optional,
+                format!("match val {{ Some(val) => {convert}, None => JObject::null().as_raw() }}"),
+                convert
+            );
+
+            let mut frees = Vec::new();
+
+            if self.is_consumed {
+                frees.push(format!(
+                    "{}{}let it = Box::from_raw(ptr as *mut {class_c});",
+                    cx.unleak("ptr"),
+                    cx.unguard()
                 ));
+
+                for field in fields {
+                    if !field.is_primitive() {
+                        let free = cx.unleak(&format!("it.{}", field.name));
+
+                        frees.push(format!("{free}let _ = Box::from_raw(it.{});", field.name));
+                    }
+                }
             }
+
+            let frees = frees
+                .iter()
+                .map(|it| format!("\n    {it}"))
+                .collect::<String>();
+
+            return format!(
+                "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {args}) -> jlong {{
+    {pre}
+
+    let task = {target}__wrapped_{method}({args_nt});{frees}
+
+    spawn_task(&mut env, &future, task, |env, val| {convert})
+}}"
+            );
         }
 
         if self.is_init {
+            let it = cx.leak("it", &class_c, &cx.java_name());
+
             if self.is_optional {
                 format!(
                     "{RUST_BRIDGE_HEAD}
@@ -171,7 +306,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
     let it = {class}::__wrapped_{method}({args_nt});
 
     if let Some(it) = it {{
-        (Box::leak(Box::new(it)) as *mut {class_c}) as {ret}
+        ({it}) as {ret}
     }} else {{
         JObject::null().as_raw() as {ret}
     }}
@@ -183,7 +318,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
 pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {args}) -> {ret} {{
     {pre}
     let it = {class}::__wrapped_{method}({args_nt});
-    (Box::leak(Box::new(it)) as *mut {class_c}) as {ret}
+    ({it}) as {ret}
 }}"
                 )
             }
@@ -217,10 +352,13 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
             } else {
                 if self.is_consumed {
                     let mut frees = Vec::new();
+                    let unleak = format!("{}{}", cx.unleak("ptr"), cx.unguard());
 
                     for field in fields {
                         if !field.is_primitive() {
-                            frees.push(format!("let _ = Box::from_raw(it.{});", field.name));
+                            let free = cx.unleak(&format!("it.{}", field.name));
+
+                            frees.push(format!("{free}let _ = Box::from_raw(it.{});", field.name));
                         }
                     }
 
@@ -233,7 +371,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
     {pre}
 
     let val = {post}it.__wrapped_{method}({args_nt}).unwrap_or_default(){post2};
-    let it = Box::from_raw(ptr as *mut {class_c});
+    {unleak}let it = Box::from_raw(ptr as *mut {class_c});
 
     {frees}
 
@@ -251,7 +389,7 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
     {pre}
 
     let val = {post}it.__wrapped_{method}({args_nt}){post2};
-    let it = Box::from_raw(ptr as *mut {class_c});
+    {unleak}let it = Box::from_raw(ptr as *mut {class_c});
     {frees}
 
     {cpost}val{cpost2}
@@ -293,22 +431,27 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
     pub fn native_rust_wrapper_code(&self, cx: &ClassCtx) -> String {
         let class = &cx.name;
         let method = &self.name;
-        let tclass = self.object.clone().unwrap_or(class.clone());
-        let tmethod = self.custom_name.clone().unwrap_or(method.clone());
+        let tclass = self.object.clone().unwrap_or_else(|| {
+            self.inherited
+                .as_ref()
+                .map_or(class.clone(), |it| it.base.clone())
+        });
+        let tmethod = self.rust_path();
         let mut args = Vec::new();
         let mut args_nt = Vec::new();
         let m_mut = if_else!(self.is_mut, "mut ", "");
 
+        // Operators like `Add` take `self` by value, but that's only a copy, so
+        // the object isn't consumed.
+        let by_value = self.is_consumed || self.operator.is_some_and(|it| it.by_value());
+
+        // Mutable methods work on a copy, which is written back afterwards.
+        let write_back = self.is_mut && !by_value;
+
         if !self.is_static {
-            if self.is_consumed {
-                args_nt.push("self.to_rust()".into());
-            } else {
-                if self.is_mut {
-                    args_nt.push("&mut self.to_rust()".into());
-                } else {
-                    args_nt.push("&self.to_rust()".into());
-                }
-            }
+            let it = if_else!(write_back, "base", "self.to_rust()");
+
+            args_nt.push(self.receiver(it, by_value));
         }
 
         for arg in &self.args {
@@ -330,6 +473,29 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
 
         let mut pre = if_else!(self.boxed, "Box::new(", "").to_string();
         let mut post = if_else!(self.boxed, ")", "").to_string();
+        let mut clone = if_else!(by_value, "", ".clone()");
+
+        // Iterators are boxed, wrapping their items if needed. They're not cloned,
+        // so they can't borrow from the object.
+        if self.ret.kind == TypeKind::Iter {
+            let item = self.ret.item_type();
+
+            pre = "Box::new(".into();
+            post = ".into_iter()".into();
+            clone = "";
+
+            if !item.kind.is_primitive()
+                && !cx.generics.iter().any(|v| v.name == item.kind.rust_name())
+            {
+                ret = format!("Box<dyn Iterator<Item = __JNI_{}>>", item.full_type());
+                post.push_str(&format!(
+                    ".map(|it| unsafe {{ __JNI_{}::of(it) }})",
+                    item.kind.rust_name()
+                ));
+            }
+
+            post.push(')');
+        }
 
         if !self.ret.kind.is_primitive()
             && cx
@@ -348,6 +514,10 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
             ret = format!("Option<{}>", ret);
         }
 
+        if self.is_async {
+            return self.native_rust_async_wrapper_code(&tclass, &tmethod, &ret, &pre, &post);
+        }
+
         if self.is_static {
             if self.is_init {
                 if self.is_optional {
@@ -371,15 +541,74 @@ pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>({base_args}, {ar
                 }
             }
         } else {
-            if self.is_optional {
+            let body = if self.is_optional {
                 format!(
-                    "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn __wrapped_{method}(&{m_mut}self, {args}) -> {ret} {{\n        let val = {tclass}::{tmethod}({args_nt});\n        if let Some(val) = val {{\n            Some({pre}val.clone(){post})\n        }} else {{\n            None\n        }}\n    }}"
+                    "let val = {tclass}::{tmethod}({args_nt});\n        if let Some(val) = val {{\n            Some({pre}val{clone}{post})\n        }} else {{\n            None\n        }}"
                 )
             } else {
+                format!("{pre}{tclass}::{tmethod}({args_nt}){clone}{post}")
+            };
+
+            let body = if_else!(
+                write_back,
                 format!(
-                    "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn __wrapped_{method}(&{m_mut}self, {args}) -> {ret} {{\n        {pre}{tclass}::{tmethod}({args_nt}).clone(){post}\n    }}"
-                )
-            }
+                    "let mut base = self.to_rust();\n        let val = {{\n            {}\n        }};\n\n        self.__set(base);\n        val",
+                    body.replace("\n", "\n    ")
+                ),
+                body
+            );
+
+            format!(
+                "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn __wrapped_{method}(&{m_mut}self, {args}) -> {ret} {{\n        {body}\n    }}"
+            )
         }
     }
+
+    /// Generate the impl for the wrapper struct for an `async` method.
+    /// The returned future owns everything it uses, so it can be spawned.
+    fn native_rust_async_wrapper_code(
+        &self,
+        tclass: &str,
+        tmethod: &str,
+        ret: &str,
+        pre: &str,
+        post: &str,
+    ) -> String {
+        let method = &self.name;
+        let m_mut = if_else!(self.is_mut, "mut ", "");
+        let mut args = Vec::new();
+        let mut args_nt = Vec::new();
+
+        let this = if self.is_static {
+            ""
+        } else if self.is_consumed {
+            args_nt.push(self.receiver("this", true));
+            "let this = self.to_rust();\n        "
+        } else {
+            args_nt.push(self.receiver("this", false));
+            "let this = self.to_rust();\n        "
+        };
+
+        for arg in &self.args {
+            let borrow = if_else!(arg.borrow, "&", "");
+            let mut_ = if_else!(arg.mutable, "mut ", "");
+
+            args.push(format!("{mut_}{}: {}", arg.name, arg.ty.full_type()));
+            args_nt.push(format!("{borrow}{mut_}{}", arg.name));
+        }
+
+        let receiver = if_else!(self.is_static, "".into(), format!("&{m_mut}self, "));
+        let args = args.join(", ");
+        let args_nt = args_nt.join(", ");
+
+        let output = if self.is_optional {
+            format!("{tclass}::{tmethod}({args_nt}).await.map(|val| {pre}val{post})")
+        } else {
+            format!("{pre}{tclass}::{tmethod}({args_nt}).await{post}")
+        };
+
+        format!(
+            "    {RUST_BRIDGE_HEAD_MANGLE}\n    pub unsafe fn __wrapped_{method}({receiver}{args}) -> impl std::future::Future<Output = {ret}> + Send + 'static {{\n        {this}async move {{ {output} }}\n    }}"
+        )
+    }
 }
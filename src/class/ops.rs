@@ -0,0 +1,102 @@
+//! Operator traits, mapped to Java methods and Kotlin `operator fun`s.
+
+/// An operator trait from `std::ops`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+    Neg,
+    Not,
+    Index,
+    AddAssign,
+    SubAssign,
+    MulAssign,
+    DivAssign,
+    RemAssign,
+}
+
+impl Operator {
+    /// Get an [`Operator`] from its trait name.
+    pub fn from_name(name: impl AsRef<str>) -> Option<Self> {
+        Some(match name.as_ref() {
+            "Add" => Self::Add,
+            "Sub" => Self::Sub,
+            "Mul" => Self::Mul,
+            "Div" => Self::Div,
+            "Rem" => Self::Rem,
+            "Neg" => Self::Neg,
+            "Not" => Self::Not,
+            "Index" => Self::Index,
+            "AddAssign" => Self::AddAssign,
+            "SubAssign" => Self::SubAssign,
+            "MulAssign" => Self::MulAssign,
+            "DivAssign" => Self::DivAssign,
+            "RemAssign" => Self::RemAssign,
+            _ => return None,
+        })
+    }
+
+    /// Get the name of the trait's method in Rust.
+    pub fn rust_name(&self) -> &'static str {
+        match self {
+            Self::Add => "add",
+            Self::Sub => "sub",
+            Self::Mul => "mul",
+            Self::Div => "div",
+            Self::Rem => "rem",
+            Self::Neg => "neg",
+            Self::Not => "not",
+            Self::Index => "index",
+            Self::AddAssign => "add_assign",
+            Self::SubAssign => "sub_assign",
+            Self::MulAssign => "mul_assign",
+            Self::DivAssign => "div_assign",
+            Self::RemAssign => "rem_assign",
+        }
+    }
+
+    /// Get the name of the method in Java, which is the name of the matching
+    /// Kotlin operator.
+    pub fn java_name(&self) -> &'static str {
+        match self {
+            Self::Add => "plus",
+            Self::Sub => "minus",
+            Self::Mul => "times",
+            Self::Div => "div",
+            Self::Rem => "rem",
+            Self::Neg => "unaryMinus",
+            Self::Not => "not",
+            Self::Index => "get",
+            Self::AddAssign => "plusAssign",
+            Self::SubAssign => "minusAssign",
+            Self::MulAssign => "timesAssign",
+            Self::DivAssign => "divAssign",
+            Self::RemAssign => "remAssign",
+        }
+    }
+
+    /// Get the name of the argument, if the operator takes one.
+    pub fn arg_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Neg | Self::Not => None,
+            Self::Index => Some("index"),
+            _ => Some("other"),
+        }
+    }
+
+    /// Does the trait's method take `self` by value?
+    pub fn by_value(&self) -> bool {
+        !self.is_assign() && *self != Self::Index
+    }
+
+    /// Is this a compound assignment, modifying the object?
+    pub fn is_assign(&self) -> bool {
+        matches!(
+            self,
+            Self::AddAssign | Self::SubAssign | Self::MulAssign | Self::DivAssign | Self::RemAssign
+        )
+    }
+}
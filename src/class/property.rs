@@ -0,0 +1,159 @@
+//! Properties backed by Rust methods.
+
+use super::{
+    base::RUST_BRIDGE_HEAD,
+    conv::wrapper_prefix,
+    ctx::ClassCtx,
+    field::{Access, Field},
+    ty::{Type, TypeKind},
+};
+use crate::if_else;
+
+/// A property, like `property name: String = get_name / set_name;`. Java sees
+/// it as a field, but it goes through the Rust getter and setter methods,
+/// so any invariants they enforce still hold.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Property {
+    /// The name of this property.
+    pub name: String,
+
+    /// This property's [`Type`].
+    pub ty: Type,
+
+    /// The name of the Rust getter method.
+    pub getter: String,
+
+    /// The name of the Rust setter method. Without one, it's read-only.
+    pub setter: Option<String>,
+}
+
+impl Property {
+    /// Get the [`Field`] Java sees.
+    pub fn field(&self) -> Field {
+        Field {
+            access: if_else!(self.setter.is_some(), Access::ReadWrite, Access::ReadOnly),
+            ..Field::new(&self.name, self.ty.clone())
+        }
+    }
+
+    /// Generate Rust code for the getter.
+    pub fn rust_getter(&self, cx: &ClassCtx) -> String {
+        let name = cx.method_name(format!("jni_get_{}", self.name));
+        let class = cx.name_generics();
+        let trace = cx.instrument_block(format!("get_{}", self.name), &[]);
+        let guard = cx.guard_block(false);
+        let getter = &self.getter;
+        let kind = &self.ty.kind;
+        let ret = kind.jni_name();
+
+        let generics = cx
+            .generics
+            .iter()
+            .map(|v| v.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let value = format!("it.to_rust().{getter}()");
+
+        let value = if *kind == TypeKind::Bool {
+            format!("{value} as u8")
+        } else if kind.is_number() {
+            format!("{value} as {ret}")
+        } else if *kind == TypeKind::String {
+            format!("env.new_string({value}).unwrap().as_raw()")
+        } else if let Some(convert) = kind.to_jobject_call("&mut env", &value) {
+            convert
+        } else {
+            let other = self.ty.full_type();
+            let j = wrapper_prefix(&self.ty, &cx.generics);
+            let value = if_else!(j.is_empty(), value, format!("{j}{other}::of({value})"));
+
+            let label = if_else!(
+                j.is_empty(),
+                format!("{}<{other}>", cx.java_name()),
+                format!("{}.{other}", cx.package)
+            );
+            let ptr = cx.leak(&value, &format!("{j}{other}"), &label);
+
+            format!("({ptr}) as jlong")
+        };
+
+        format!(
+            "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+) -> {ret} {{
+{trace}{guard}    let it = &*(ptr as *mut {class});
+
+    {value}
+}}"
+        )
+    }
+
+    /// Generate Rust code for the setter, if there is one. The object is
+    /// changed through the setter on a copy, which is written back.
+    pub fn rust_setter(&self, cx: &ClassCtx) -> Option<String> {
+        let setter = self.setter.as_ref()?;
+        let name = cx.method_name(format!("jni_set_{}", self.name));
+        let class = cx.name_generics();
+        let trace = cx.instrument_block(format!("set_{}", self.name), &[("val", &self.ty)]);
+        let guard = cx.guard_block(true);
+        let kind = &self.ty.kind;
+
+        let generics = cx
+            .generics
+            .iter()
+            .map(|v| v.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let (val_ty, value) = match kind {
+            TypeKind::Bool => ("jboolean".into(), "val == 1".into()),
+            TypeKind::U8 => ("jbyte".into(), "val as u8".into()),
+            TypeKind::U16 => ("jshort".into(), "val as u16".into()),
+            TypeKind::U32 => ("jint".into(), "val as u32".into()),
+            TypeKind::U64 => ("jlong".into(), "val as u64".into()),
+            TypeKind::Char => (
+                "jchar".into(),
+                "char::from_u32(val as u32).unwrap_or_default()".into(),
+            ),
+            TypeKind::String => (
+                "JString<'local>".into(),
+                "env.get_string(&val).unwrap().to_str().unwrap().to_string()".into(),
+            ),
+            TypeKind::Other(_) => {
+                let j = wrapper_prefix(&self.ty, &cx.generics);
+                let convert = if_else!(j.is_empty(), "clone", "to_rust");
+
+                (
+                    "jlong".into(),
+                    format!("(&*(val as *mut {j}{})).{convert}()", self.ty.full_type()),
+                )
+            }
+            _ => match kind.from_jobject_call("&mut env", "&val") {
+                Some(convert) => ("JObject<'local>".into(), convert),
+                None => (kind.jni_name(), "val".into()),
+            },
+        };
+
+        Some(format!(
+            "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{name}<'local, {generics}>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+    val: {val_ty},
+) -> jlong {{
+{trace}{guard}    let it = &mut *(ptr as *mut {class});
+    let mut base = it.to_rust();
+
+    base.{setter}({value});
+    it.__set(base);
+
+    ptr as jlong
+}}"
+        ))
+    }
+}
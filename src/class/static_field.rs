@@ -0,0 +1,204 @@
+//! Static fields.
+
+use convert_case::{Case, Casing};
+use std::collections::BTreeMap;
+
+use super::{
+    base::RUST_BRIDGE_HEAD,
+    ctx::ClassCtx,
+    ty::{Type, TypeKind},
+};
+use crate::{
+    codegen::java::{JCall, JExpr, JExternMethod, JMember, JMethodImpl, JType},
+    if_else,
+};
+
+/// A static field in a class, like `static field COUNTER: AtomicU64;`, bound
+/// to a Rust `static` with the same name. Atomics can be read and written,
+/// anything else can only be read, since Rust statics are immutable.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StaticField {
+    /// The name of this field, and of the Rust `static`.
+    pub name: String,
+
+    /// This field's [`Type`].
+    pub ty: Type,
+}
+
+impl StaticField {
+    /// If this is an atomic, get the type it holds and its name in Rust.
+    pub fn atomic(&self) -> Option<(TypeKind, &'static str)> {
+        if self.ty.generics.is_some() {
+            return None;
+        }
+
+        Some(match self.ty.kind.rust_name().as_str() {
+            "AtomicBool" => (TypeKind::Bool, "bool"),
+            "AtomicI8" => (TypeKind::I8, "i8"),
+            "AtomicI16" => (TypeKind::I16, "i16"),
+            "AtomicI32" => (TypeKind::I32, "i32"),
+            "AtomicI64" => (TypeKind::I64, "i64"),
+            "AtomicIsize" => (TypeKind::I64, "isize"),
+            "AtomicU8" => (TypeKind::U8, "u8"),
+            "AtomicU16" => (TypeKind::U16, "u16"),
+            "AtomicU32" => (TypeKind::U32, "u32"),
+            "AtomicU64" => (TypeKind::U64, "u64"),
+            "AtomicUsize" => (TypeKind::U64, "usize"),
+            _ => return None,
+        })
+    }
+
+    /// Get the type Java sees. Atomics are their value.
+    pub fn value_type(&self) -> Type {
+        match self.atomic() {
+            Some((kind, _)) => Type::new(kind, None),
+            None => self.ty.clone(),
+        }
+    }
+
+    /// Generate the native method code for Java.
+    pub fn native_java_code(&self) -> Vec<JMember> {
+        let ty = self.value_type();
+        let j_ty = if_else!(ty.kind.is_primitive(), ty.j_type(), JType::Long);
+        let mut natives = Vec::new();
+
+        natives.push(JMember::ExternMethod(JExternMethod {
+            name: format!("jni_get_{}", self.name),
+            private: true,
+            is_static: true,
+            ret: j_ty.clone(),
+            args: Vec::new(),
+        }));
+
+        if self.atomic().is_some() {
+            natives.push(JMember::ExternMethod(JExternMethod {
+                name: format!("jni_set_{}", self.name),
+                private: true,
+                is_static: true,
+                ret: JType::Void,
+                args: vec![("value".into(), j_ty)],
+            }));
+        }
+
+        natives
+    }
+
+    /// Generate the static getter (and setter) code for Java.
+    pub fn wrapper_java_code(&self, cx: &ClassCtx) -> Vec<JMember> {
+        let ty = self.value_type();
+        let holder = if_else!(cx.value, "Native.", "");
+        let mut wrappers = Vec::new();
+
+        let get = JExpr::Call(JCall {
+            target: format!("{holder}jni_get_{}", self.name),
+            args: Vec::new(),
+        });
+
+        let get = if_else!(
+            ty.kind.is_primitive(),
+            get,
+            JExpr::Call(JCall {
+                target: format!("{}.from", ty.full_type_java()),
+                args: vec![get],
+            })
+        );
+
+        wrappers.push(JMember::MethodImpl(JMethodImpl {
+            name: format!("get_{}", self.name).to_case(Case::Camel),
+            ret: ty.j_type(),
+            args: Vec::new(),
+            is_static: true,
+            private: false,
+            is_override: false,
+            is_suspend: false,
+            is_operator: false,
+            generics: BTreeMap::new(),
+            code: vec![JExpr::Return(Box::new(get))],
+        }));
+
+        if self.atomic().is_some() {
+            wrappers.push(JMember::MethodImpl(JMethodImpl {
+                name: format!("set_{}", self.name).to_case(Case::Camel),
+                ret: JType::Void,
+                args: vec![("value".into(), ty.j_type())],
+                is_static: true,
+                private: false,
+                is_override: false,
+                is_suspend: false,
+                is_operator: false,
+                generics: BTreeMap::new(),
+                code: vec![JExpr::Call(JCall {
+                    target: format!("{holder}jni_set_{}", self.name),
+                    args: vec![JExpr::Name("value".into())],
+                })],
+            }));
+        }
+
+        wrappers
+    }
+
+    /// Generate the native method code for Rust.
+    pub fn native_rust_code(&self, cx: &ClassCtx) -> Vec<String> {
+        let field = &self.name;
+        let get = cx.method_name(format!("jni_get_{field}"));
+        let set = cx.method_name(format!("jni_set_{field}"));
+        let ordering = "::std::sync::atomic::Ordering::SeqCst";
+        let ret = self.value_type().kind.jni_name();
+        let value_ty = self.value_type();
+        let get_trace = cx.instrument_block(format!("get_{field}"), &[]);
+        let set_trace = cx.instrument_block(format!("set_{field}"), &[("value", &value_ty)]);
+        let mut code = Vec::new();
+
+        let value = if let Some((kind, _)) = self.atomic() {
+            let cast = if_else!(kind == TypeKind::Bool, "u8", ret.as_str());
+
+            format!("{field}.load({ordering}) as {cast}")
+        } else if self.ty.kind == TypeKind::Bool {
+            format!("{field} as u8")
+        } else if self.ty.kind.is_number() {
+            format!("{field} as {ret}")
+        } else if self.ty.kind == TypeKind::String {
+            format!("env.new_string({field}.to_string()).unwrap().as_raw()")
+        } else if let Some(convert) = self
+            .ty
+            .kind
+            .to_jobject_call("&mut env", &format!("{field}.clone()"))
+        {
+            convert
+        } else {
+            let class = self.ty.full_type();
+
+            let ptr = cx.leak(
+                &format!("__JNI_{class}::of({field}.clone())"),
+                &format!("__JNI_{class}"),
+                &format!("{}.{class}", cx.package),
+            );
+
+            format!("({ptr}) as jlong")
+        };
+
+        code.push(format!(
+            "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{get}<'local>(mut env: JNIEnv<'local>, class: JClass<'local>) -> {ret} {{
+{get_trace}    {value}
+}}"
+        ));
+
+        if let Some((kind, rust)) = self.atomic() {
+            let value = if_else!(
+                kind == TypeKind::Bool,
+                "value == 1".to_string(),
+                format!("value as {rust}")
+            );
+
+            code.push(format!(
+                "{RUST_BRIDGE_HEAD}
+pub unsafe extern \"system\" fn Java_{set}<'local>(mut env: JNIEnv<'local>, class: JClass<'local>, value: {ret}) {{
+{set_trace}    {field}.store({value}, {ordering});
+}}"
+            ));
+        }
+
+        code
+    }
+}
@@ -0,0 +1,47 @@
+//! Thread-safety modifiers, like `sync class Foo`.
+
+/// How a class's objects can be used from different Java threads.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Threading {
+    /// No modifier. Any thread can call any method, so callers have to make
+    /// sure a `mut` method isn't running while the object is used elsewhere.
+    #[default]
+    Unchecked,
+
+    /// `sync`, for `Send + Sync` types. Methods taking `&self` can run at the
+    /// same time, and the others lock the object.
+    Sync,
+
+    /// `send`, for `Send` types. Every method locks the object.
+    Send,
+
+    /// `local`, for any type. Using an object from a thread other than the
+    /// one that created it throws an `IllegalStateException`.
+    Local,
+}
+
+impl Threading {
+    /// Get a [`Threading`] from its modifier.
+    pub fn from_name(name: impl AsRef<str>) -> Option<Self> {
+        match name.as_ref() {
+            "sync" => Some(Self::Sync),
+            "send" => Some(Self::Send),
+            "local" => Some(Self::Local),
+            _ => None,
+        }
+    }
+
+    /// Get the traits the Rust type has to implement, if any.
+    pub fn bounds(&self) -> Option<&'static str> {
+        match self {
+            Self::Sync => Some("Send + Sync"),
+            Self::Send => Some("Send"),
+            Self::Unchecked | Self::Local => None,
+        }
+    }
+
+    /// Are objects guarded?
+    pub fn is_guarded(&self) -> bool {
+        *self != Self::Unchecked
+    }
+}
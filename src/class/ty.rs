@@ -20,6 +20,10 @@ impl Type {
 
     /// Get the full Rust type.
     pub fn full_type(&self) -> String {
+        if self.kind == TypeKind::Iter {
+            return format!("Box<dyn Iterator<Item = {}>>", self.get_generics());
+        }
+
         let g = if_else!(
             self.generics.is_some(),
             format!("<{}>", self.get_generics()),
@@ -53,6 +57,15 @@ impl Type {
         format!("{}{}", self.kind.java_name(), g)
     }
 
+    /// Get the element type of an [`TypeKind::Iter`].
+    pub fn item_type(&self) -> Type {
+        self.generics
+            .as_ref()
+            .and_then(|it| it.first())
+            .cloned()
+            .unwrap_or_default()
+    }
+
     /// Get the generics for this type for Java.
     pub fn get_generics_java(&self) -> String {
         let mut generics = Vec::new();
@@ -71,11 +84,69 @@ impl Type {
         self.kind.convert_func()
     }
 
+    /// Turn references to value classes into [`TypeKind::Value`]s.
+    pub fn resolve_values(&mut self, values: &[String]) {
+        if let TypeKind::Other(name) = &self.kind
+            && values.contains(name)
+        {
+            self.kind = TypeKind::Value(name.clone());
+        }
+
+        if let TypeKind::Tuple(items) = &mut self.kind {
+            for it in items {
+                it.resolve_values(values);
+            }
+        }
+
+        if let TypeKind::Slice(item) = &mut self.kind {
+            item.resolve_values(values);
+        }
+
+        for it in self.generics.iter_mut().flatten() {
+            it.resolve_values(values);
+        }
+    }
+
+    /// Replace a generic with a concrete [`Type`].
+    pub fn substitute(&self, name: &str, with: &Type) -> Type {
+        if self.kind == TypeKind::Other(name.into()) && self.generics.is_none() {
+            return with.clone();
+        }
+
+        let kind = match &self.kind {
+            TypeKind::Tuple(items) => {
+                TypeKind::Tuple(items.iter().map(|it| it.substitute(name, with)).collect())
+            }
+
+            TypeKind::Slice(item) => TypeKind::Slice(Box::new(item.substitute(name, with))),
+
+            kind => kind.clone(),
+        };
+
+        let generics = self
+            .generics
+            .as_ref()
+            .map(|it| it.iter().map(|it| it.substitute(name, with)).collect());
+
+        Type { kind, generics }
+    }
+
+    /// Does this use a generic anywhere?
+    pub fn contains(&self, name: &str) -> bool {
+        self.kind == TypeKind::Other(name.into())
+            || matches!(&self.kind, TypeKind::Tuple(items) if items.iter().any(|it| it.contains(name)))
+            || matches!(&self.kind, TypeKind::Slice(item) if item.contains(name))
+            || self.generics.iter().flatten().any(|it| it.contains(name))
+    }
+
     pub fn j_type(&self) -> JType {
         if let Some(it) = &self.generics {
             JType::Generic {
                 base: Box::new(self.kind.j_type()),
-                params: it.iter().map(|it| it.j_type()).collect(),
+                params: it
+                    .iter()
+                    .map(|it| JType::Boxed(Box::new(it.j_type())))
+                    .collect(),
             }
         } else {
             self.kind.j_type()
@@ -129,6 +200,30 @@ pub enum TypeKind {
     /// A [`char`] (Java: `char`).
     Char,
 
+    /// A [`std::time::Duration`] (Java: `java.time.Duration`).
+    Duration,
+
+    /// A [`std::time::SystemTime`] (Java: `java.time.Instant`).
+    SystemTime,
+
+    /// A [`std::path::PathBuf`] (Java: `java.nio.file.Path`).
+    PathBuf,
+
+    /// A boxed Rust [`Iterator`], only usable as a return type
+    /// (Java: `NativeIterator`, a `java.util.Iterator`).
+    Iter,
+
+    /// A slice of values, only usable as an argument, like `&[T]` or varargs
+    /// (`...T`). Rust gets a [`Vec`], copied out of a Java array.
+    Slice(Box<Type>),
+
+    /// A tuple, copied into a generated record (Java: `record`, Kotlin:
+    /// `Pair`, `Triple` or a `data class`).
+    Tuple(Vec<Type>),
+
+    /// A `value class`, copied into a Java `record` (Kotlin: `data class`).
+    Value(String),
+
     /// A type that is non-primitive.
     Other(String),
 }
@@ -159,14 +254,35 @@ impl TypeKind {
             Self::F64 => "f64".into(),
             Self::Bool => "bool".into(),
             Self::Char => "char".into(),
-            Self::Other(o) => o.to_owned(),
+            Self::Duration => "std::time::Duration".into(),
+            Self::SystemTime => "std::time::SystemTime".into(),
+            Self::PathBuf => "std::path::PathBuf".into(),
+            Self::Iter => "Iter".into(),
+            Self::Tuple(items) => format!(
+                "({})",
+                items
+                    .iter()
+                    .map(|it| it.full_type())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::Slice(item) => format!("Vec<{}>", item.full_type()),
+            Self::Value(o) | Self::Other(o) => o.to_owned(),
         }
     }
 
     /// Can we cast from a JNI type to the Rust type?
     pub fn can_cast(&self) -> bool {
         match self {
-            Self::String | Self::Other(_) => false,
+            Self::String
+            | Self::Duration
+            | Self::SystemTime
+            | Self::PathBuf
+            | Self::Iter
+            | Self::Slice(_)
+            | Self::Tuple(_)
+            | Self::Value(_)
+            | Self::Other(_) => false,
             _ => true,
         }
     }
@@ -184,14 +300,20 @@ impl TypeKind {
             Self::F64 => "double".into(),
             Self::Bool => "boolean".into(),
             Self::Char => "char".into(),
-            Self::Other(o) => o.to_owned(),
+            Self::Duration => "java.time.Duration".into(),
+            Self::SystemTime => "java.time.Instant".into(),
+            Self::PathBuf => "java.nio.file.Path".into(),
+            Self::Iter => "NativeIterator".into(),
+            Self::Slice(item) => format!("{}[]", item.full_type_java()),
+            Self::Tuple(_) => self.tuple_name(),
+            Self::Value(o) | Self::Other(o) => o.to_owned(),
         }
     }
 
     /// Get the type's name in Java.
     pub fn native_name(&self) -> String {
         match self {
-            Self::Other(_) => "long".into(),
+            Self::Iter | Self::Other(_) => "long".into(),
             _ => self.java_name(),
         }
     }
@@ -227,7 +349,11 @@ impl TypeKind {
             Self::F64 => "jdouble".into(),
             Self::Bool => "jboolean".into(),
             Self::Char => "jchar".into(),
-            Self::Other(_) => "jlong".into(),
+            Self::Duration | Self::SystemTime | Self::PathBuf | Self::Tuple(_) | Self::Value(_) => {
+                "jobject".into()
+            }
+            Self::Slice(_) => "jarray".into(),
+            Self::Iter | Self::Other(_) => "jlong".into(),
         }
     }
 
@@ -244,7 +370,15 @@ impl TypeKind {
             Self::F64 => JType::Double,
             Self::Bool => JType::Bool,
             Self::Char => JType::Char,
-            Self::Other(it) => JType::Custom(it.clone()),
+            Self::Duration | Self::SystemTime | Self::PathBuf | Self::Iter => {
+                JType::Custom(self.java_name())
+            }
+            Self::Slice(item) => JType::Array(Box::new(item.j_type())),
+            Self::Tuple(items) => JType::Tuple {
+                name: self.tuple_name(),
+                items: items.iter().map(|it| it.j_type()).collect(),
+            },
+            Self::Value(it) | Self::Other(it) => JType::Custom(it.clone()),
         }
     }
 
@@ -252,10 +386,136 @@ impl TypeKind {
     pub fn jni_arg_name(&self) -> String {
         match self {
             Self::String => "JString<'local>".into(),
+            Self::Duration | Self::SystemTime | Self::PathBuf | Self::Tuple(_) | Self::Value(_) => {
+                "JObject<'local>".into()
+            }
+            Self::Slice(item) if item.kind.is_number() || item.kind == Self::Bool => {
+                format!("JPrimitiveArray<'local, {}>", item.kind.jni_name())
+            }
+            Self::Slice(_) => "JObjectArray<'local>".into(),
             _ => self.jni_name(),
         }
     }
 
+    /// Is this a standard library type that crosses the bridge as a Java object?
+    pub fn is_std_object(&self) -> bool {
+        matches!(self, Self::Duration | Self::SystemTime | Self::PathBuf)
+    }
+
+    /// Get the function converting a Java object into this type, if there is one.
+    /// Tuples use functions generated alongside the bindings.
+    pub fn from_jobject_func(&self) -> Option<String> {
+        match self {
+            Self::Duration => Some("duration_from_jobject".into()),
+            Self::SystemTime => Some("system_time_from_jobject".into()),
+            Self::PathBuf => Some("path_from_jobject".into()),
+            Self::Tuple(_) => Some(format!("__tuple_from_jobject_{}", self.tuple_id())),
+            Self::Value(it) => Some(format!("__value_from_jobject_{it}")),
+            _ => None,
+        }
+    }
+
+    /// Get the function converting this type into a Java object, if there is one.
+    /// Tuples use functions generated alongside the bindings.
+    pub fn to_jobject_func(&self) -> Option<String> {
+        match self {
+            Self::Duration => Some("duration_to_jobject".into()),
+            Self::SystemTime => Some("system_time_to_jobject".into()),
+            Self::PathBuf => Some("path_to_jobject".into()),
+            Self::Tuple(_) => Some(format!("__tuple_to_jobject_{}", self.tuple_id())),
+            Self::Value(it) => Some(format!("__value_to_jobject_{it}")),
+            _ => None,
+        }
+    }
+
+    /// Get an expression converting the Java object `var` into this type in a
+    /// native, if there's a function for it. The standard library conversions
+    /// return [`Result`]s, and the native returns early if they throw.
+    pub fn from_jobject_call(&self, env: &str, var: &str) -> Option<String> {
+        let func = self.from_jobject_func()?;
+
+        Some(if_else!(
+            self.is_std_object(),
+            format!(
+                "match {func}({env}, {var}) {{ Ok(it) => it, Err(_) => return std::mem::zeroed() }}"
+            ),
+            format!("{func}({env}, {var})")
+        ))
+    }
+
+    /// Get an expression converting `var` into a `jobject`, if there's a
+    /// function for it. The standard library conversions give Java `null` if
+    /// they throw, leaving the exception pending.
+    pub fn to_jobject_call(&self, env: &str, var: &str) -> Option<String> {
+        let func = self.to_jobject_func()?;
+
+        Some(if_else!(
+            self.is_std_object(),
+            format!("{func}({env}, {var}).unwrap_or(std::ptr::null_mut())"),
+            format!("{func}({env}, {var})")
+        ))
+    }
+
+    /// Get a short, identifier-safe name for this type, used to name tuple records.
+    pub fn short_name(&self) -> String {
+        match self {
+            Self::Void => "Void".into(),
+            Self::String => "String".into(),
+            Self::I8 | Self::U8 => "Byte".into(),
+            Self::I16 | Self::U16 => "Short".into(),
+            Self::I32 | Self::U32 => "Int".into(),
+            Self::I64 | Self::U64 => "Long".into(),
+            Self::F32 => "Float".into(),
+            Self::F64 => "Double".into(),
+            Self::Bool => "Bool".into(),
+            Self::Char => "Char".into(),
+            Self::Duration => "Duration".into(),
+            Self::SystemTime => "Instant".into(),
+            Self::PathBuf => "Path".into(),
+            Self::Iter => "Iter".into(),
+            Self::Slice(item) => format!("{}Array", item.kind.short_name()),
+            Self::Tuple(_) => self.tuple_name(),
+            Self::Value(it) | Self::Other(it) => it.clone(),
+        }
+    }
+
+    /// Get the name of the record generated for a tuple, like `TupleIntString`.
+    /// Tuples whose items map to the same Java types share a record.
+    pub fn tuple_name(&self) -> String {
+        match self {
+            Self::Tuple(items) => format!(
+                "Tuple{}",
+                items
+                    .iter()
+                    .map(|it| {
+                        let generics = it
+                            .generics
+                            .iter()
+                            .flatten()
+                            .map(|it| it.kind.short_name())
+                            .collect::<String>();
+
+                        format!("{}{generics}", it.kind.short_name())
+                    })
+                    .collect::<String>()
+            ),
+            _ => self.short_name(),
+        }
+    }
+
+    /// Get an identifier for a tuple's Rust type, used to name its conversion functions.
+    pub fn tuple_id(&self) -> String {
+        let name = self.rust_name();
+
+        name.strip_prefix('(')
+            .and_then(|it| it.strip_suffix(')'))
+            .unwrap_or(&name)
+            .chars()
+            .filter(|it| !it.is_whitespace())
+            .map(|it| if_else!(it.is_ascii_alphanumeric(), it, '_'))
+            .collect()
+    }
+
     /// Get the conversion function for this type
     pub fn convert_func(&self) -> String {
         match self {
@@ -269,6 +529,13 @@ impl TypeKind {
             Self::F64 => "NativeTools.getDouble".into(),
             Self::Bool => "NativeTools.getBool".into(),
             Self::Char => "NativeTools.getChar".into(),
+            Self::Duration
+            | Self::SystemTime
+            | Self::PathBuf
+            | Self::Iter
+            | Self::Slice(_)
+            | Self::Tuple(_)
+            | Self::Value(_) => "".into(),
             Self::Other(it) => format!("{}.from", it),
         }
     }
@@ -2,17 +2,155 @@
 
 use std::collections::BTreeMap;
 
-use convert_case::{Case, Casing};
-
 use super::{ctx::ClassCtx, method::Method, ty::TypeKind};
-use crate::codegen::java::{
-    JCall, JCtor, JExpr, JMember, JMethodImpl, JSafeFieldCall, JSetField, JType, JVar,
+use crate::{
+    codegen::{
+        cx::Generator,
+        java::{
+            JCall, JCtor, JExpr, JIf, JMember, JMethodCall, JMethodImpl, JNewCall, JSafeFieldCall,
+            JSetField, JType, JVar,
+        },
+    },
+    if_else,
 };
 
 impl Method {
     /// Generate Java code for this method.
-    pub fn wrapper_java_code(&self, cx: &ClassCtx) -> JMember {
-        let native = &self.calls();
+    pub fn wrapper_java_code(&self, cx: &ClassCtx, gcx: &Generator) -> JMember {
+        if !self.has_native() {
+            return self.dispatch_java_code(gcx);
+        }
+
+        self.overload_java_code(cx, gcx, self.args.len())
+    }
+
+    /// Generate the Java code for a method with an erased type parameter. It
+    /// checks which class it got, and calls the instance for it.
+    fn dispatch_java_code(&self, gcx: &Generator) -> JMember {
+        let generic = &self.generics[0];
+        let kind = TypeKind::Other(generic.name.clone());
+        let name = self.java_name();
+        let mut code = Vec::new();
+
+        let Some(first) = self.args.iter().find(|it| it.ty.kind == kind) else {
+            unreachable!("erased type parameters are always an argument's type");
+        };
+
+        for ty in &generic.types {
+            let class = ty.full_type_java();
+
+            let cast = |it: &str| {
+                if_else!(
+                    gcx.kotlin,
+                    format!("{it} as {class}"),
+                    format!("({class}) {it}")
+                )
+            };
+
+            let args = self
+                .args
+                .iter()
+                .map(|it| {
+                    JExpr::Name(if_else!(
+                        it.ty.kind == kind,
+                        cast(&it.name),
+                        it.name.clone()
+                    ))
+                })
+                .collect();
+
+            let call = JExpr::Call(JCall {
+                target: name.clone(),
+                args,
+            });
+
+            let ret = if self.ret.kind == TypeKind::Void {
+                vec![call, JExpr::Name("return".into())]
+            } else if self.ret.kind == kind {
+                let call = call.code(gcx);
+
+                vec![JExpr::Return(Box::new(JExpr::Name(if_else!(
+                    gcx.kotlin,
+                    format!("{call} as {}", generic.name),
+                    format!("({}) {call}", generic.name)
+                ))))]
+            } else {
+                vec![JExpr::Return(Box::new(call))]
+            };
+
+            code.push(JExpr::If(JIf {
+                cond: Box::new(JExpr::Name(if_else!(
+                    gcx.kotlin,
+                    format!("{} is {class}", first.name),
+                    format!("{} instanceof {class}", first.name)
+                ))),
+                body: ret,
+            }));
+        }
+
+        let (new, class) = if_else!(
+            gcx.kotlin,
+            ("", "javaClass.name"),
+            ("new ", "getClass().getName()")
+        );
+
+        code.push(JExpr::Name(format!(
+            "throw {new}IllegalArgumentException(\"Can't call {name} with \" + {}.{class})",
+            first.name
+        )));
+
+        let bounds = generic
+            .bounds
+            .iter()
+            .map(|it| it.j_type().name(gcx))
+            .collect();
+
+        JMember::MethodImpl(JMethodImpl {
+            args: self
+                .args
+                .iter()
+                .map(|it| (it.name.clone(), it.java_type()))
+                .collect(),
+            generics: BTreeMap::from([(generic.name.clone(), bounds)]),
+            is_override: false,
+            is_suspend: false,
+            is_operator: false,
+            is_static: self.is_static,
+            name,
+            private: false,
+            ret: self.ret.j_type(),
+            code,
+        })
+    }
+
+    /// Generate the Java overloads for this method's default values. Each one
+    /// leaves off another argument, passing its default to the native.
+    pub fn overloads_java_code(&self, cx: &ClassCtx, gcx: &Generator) -> Vec<JMember> {
+        if !self.has_native() {
+            return Vec::new();
+        }
+
+        let Some(first) = self.args.iter().position(|it| it.default.is_some()) else {
+            return Vec::new();
+        };
+
+        (first..self.args.len())
+            .map(|given| match self.overload_java_code(cx, gcx, given) {
+                // Interfaces only have the full method.
+                JMember::MethodImpl(it) => JMember::MethodImpl(JMethodImpl {
+                    is_override: false,
+                    ..it
+                }),
+
+                it => it,
+            })
+            .collect()
+    }
+
+    /// Generate Java code for this method, taking only the first `given`
+    /// arguments and using the defaults for the rest.
+    fn overload_java_code(&self, cx: &ClassCtx, gcx: &Generator, given: usize) -> JMember {
+        let native = &self.native_target(cx);
         let name = &self.name;
         let class = &cx.name;
         let convert = self.ret.convert_func();
@@ -20,12 +158,20 @@ impl Method {
         let mut args_exprs = Vec::new();
 
         if !self.is_static {
-            args_exprs.push(JExpr::Name("__ptr".into()));
+            args_exprs.push(JExpr::Name(if_else!(cx.value, "this", "__ptr").into()));
         }
 
-        for arg in &self.args {
-            args.push((arg.name.clone(), arg.ty.j_type()));
-            args_exprs.push(arg.java_name());
+        for (i, arg) in self.args.iter().enumerate() {
+            match &arg.default {
+                Some(default) if i >= given => {
+                    args_exprs.push(JExpr::Name(default.java_value(&arg.ty.kind, gcx)));
+                }
+
+                _ => {
+                    args.push((arg.name.clone(), arg.java_type()));
+                    args_exprs.push(arg.java_name());
+                }
+            }
         }
 
         if self.is_init {
@@ -43,18 +189,32 @@ impl Method {
             });
         }
 
-        let c_name = name.to_case(Case::Camel);
+        let c_name = self.java_name();
+
+        if self.is_async {
+            return self.async_wrapper_java_code(cx, gcx, c_name, args, args_exprs);
+        }
+
+        if self.ret.kind == TypeKind::Iter {
+            return self.iter_wrapper_java_code(cx, gcx, c_name, args, args_exprs);
+        }
+
+        // Natives return primitives and generated classes as they are, and
+        // pointers for class generics.
+        let direct = self.ret.kind.is_primitive() || self.returns_object(cx);
 
         if self.is_mut {
-            if self.ret.kind.is_primitive() {
+            if direct {
                 JMember::MethodImpl(JMethodImpl {
                     args,
                     generics: BTreeMap::new(),
-                    is_override: false,
+                    is_override: self.interface.is_some(),
+                    is_suspend: false,
+                    is_operator: self.operator.is_some(),
                     is_static: self.is_static,
                     name: c_name,
                     private: false,
-                    ret: self.ret.j_type(),
+                    ret: self.java_ret(cx),
 
                     code: if self.ret.kind == TypeKind::Void {
                         vec![
@@ -76,7 +236,7 @@ impl Method {
                             JExpr::Var(JVar {
                                 mutable: false,
                                 name: "val".into(),
-                                ty: self.ret.j_type(),
+                                ty: self.java_ret(cx),
                                 value: Box::new(JExpr::Call(JCall {
                                     target: native.clone(),
                                     args: args_exprs,
@@ -98,11 +258,13 @@ impl Method {
                 JMember::MethodImpl(JMethodImpl {
                     args,
                     generics: BTreeMap::new(),
-                    is_override: false,
+                    is_override: self.interface.is_some(),
+                    is_suspend: false,
+                    is_operator: self.operator.is_some(),
                     is_static: self.is_static,
                     name: c_name,
                     private: false,
-                    ret: self.ret.j_type(),
+                    ret: self.java_ret(cx),
 
                     code: vec![
                         JExpr::Var(JVar {
@@ -130,15 +292,17 @@ impl Method {
                 })
             }
         } else {
-            if self.ret.kind.is_primitive() {
+            if direct {
                 JMember::MethodImpl(JMethodImpl {
                     args,
                     generics: BTreeMap::new(),
-                    is_override: false,
+                    is_override: self.interface.is_some(),
+                    is_suspend: false,
+                    is_operator: self.operator.is_some(),
                     is_static: self.is_static,
                     name: c_name,
                     private: false,
-                    ret: self.ret.j_type(),
+                    ret: self.java_ret(cx),
 
                     code: if self.ret.kind == TypeKind::Void {
                         vec![JExpr::Call(JCall {
@@ -156,11 +320,13 @@ impl Method {
                 JMember::MethodImpl(JMethodImpl {
                     args,
                     generics: BTreeMap::new(),
-                    is_override: false,
+                    is_override: self.interface.is_some(),
+                    is_suspend: false,
+                    is_operator: self.operator.is_some(),
                     is_static: self.is_static,
                     name: c_name,
                     private: false,
-                    ret: self.ret.j_type(),
+                    ret: self.java_ret(cx),
 
                     code: vec![JExpr::Return(Box::new(JExpr::Call(JCall {
                         target: convert,
@@ -173,4 +339,159 @@ impl Method {
             }
         }
     }
+
+    /// Generate Java code for an `async` method. Java gets a `CompletableFuture`,
+    /// and Kotlin gets a `suspend fun` awaiting it.
+    fn async_wrapper_java_code(
+        &self,
+        cx: &ClassCtx,
+        gcx: &Generator,
+        name: String,
+        args: Vec<(String, JType)>,
+        mut args_exprs: Vec<JExpr>,
+    ) -> JMember {
+        args_exprs.push(JExpr::Name("future".into()));
+
+        let future = JExpr::Var(JVar {
+            mutable: false,
+            name: "future".into(),
+            ty: self.native_future_type(),
+            value: Box::new(JExpr::New(JNewCall {
+                target: if_else!(gcx.kotlin, "CompletableFuture", "CompletableFuture<>").into(),
+                args: Vec::new(),
+            })),
+        });
+
+        let mut await_args = vec![
+            JExpr::Name("future".into()),
+            JExpr::Call(JCall {
+                target: self.native_target(cx),
+                args: args_exprs,
+            }),
+        ];
+
+        let (ret, result) = if gcx.kotlin {
+            let awaited = JExpr::MethodCall(JMethodCall {
+                target: Box::new(JExpr::Call(JCall {
+                    target: "NativeTools.awaitTask".into(),
+                    args: await_args,
+                })),
+                name: "await".into(),
+                args: Vec::new(),
+            });
+
+            let result = if self.ret.kind == TypeKind::Void {
+                awaited
+            } else if self.ret.kind.is_primitive() {
+                JExpr::Return(Box::new(awaited))
+            } else {
+                JExpr::Return(Box::new(JExpr::Call(JCall {
+                    target: self.ret.convert_func(),
+                    args: vec![awaited],
+                })))
+            };
+
+            (self.ret.j_type(), result)
+        } else {
+            if !self.ret.kind.is_primitive() {
+                await_args.push(JExpr::Name(format!("{}::from", self.ret.kind.java_name())));
+            }
+
+            let ret = JType::Generic {
+                base: Box::new(JType::Custom("CompletableFuture".into())),
+                params: vec![JType::Boxed(Box::new(self.ret.j_type()))],
+            };
+
+            let result = JExpr::Return(Box::new(JExpr::Call(JCall {
+                target: "NativeTools.awaitTask".into(),
+                args: await_args,
+            })));
+
+            (ret, result)
+        };
+
+        JMember::MethodImpl(JMethodImpl {
+            args,
+            generics: BTreeMap::new(),
+            is_override: self.interface.is_some(),
+            is_suspend: true,
+            is_operator: false,
+            is_static: self.is_static,
+            name,
+            private: false,
+            ret,
+            code: vec![future, result],
+        })
+    }
+
+    /// Generate Java code for a method returning an iterator, wrapping the
+    /// pointer in a `NativeIterator`.
+    fn iter_wrapper_java_code(
+        &self,
+        cx: &ClassCtx,
+        gcx: &Generator,
+        name: String,
+        args: Vec<(String, JType)>,
+        args_exprs: Vec<JExpr>,
+    ) -> JMember {
+        let item = self.ret.item_type();
+
+        let ptr = JExpr::Call(JCall {
+            target: self.native_target(cx),
+            args: args_exprs,
+        });
+
+        let value = if item.kind.is_primitive() {
+            JExpr::New(JNewCall {
+                target: if_else!(gcx.kotlin, "NativeIterator", "NativeIterator<>").into(),
+                args: vec![ptr],
+            })
+        } else {
+            let convert = if_else!(
+                gcx.kotlin,
+                format!("{{ {}(it) }}", item.convert_func()),
+                format!("{}::from", item.kind.java_name())
+            );
+
+            JExpr::Call(JCall {
+                target: "NativeIterator.ofPointers".into(),
+                args: vec![ptr, JExpr::Name(convert)],
+            })
+        };
+
+        let code = if self.is_mut {
+            vec![
+                JExpr::Var(JVar {
+                    mutable: false,
+                    name: "iter".into(),
+                    ty: self.ret.j_type(),
+                    value: Box::new(value),
+                }),
+                JExpr::SafeFieldCall(JSafeFieldCall {
+                    field: "__parent".into(),
+                    target: "updateField".into(),
+                    args: vec![
+                        JExpr::Name("__parentField".into()),
+                        JExpr::Name("__ptr".into()),
+                    ],
+                }),
+                JExpr::Return(Box::new(JExpr::Name("iter".into()))),
+            ]
+        } else {
+            vec![JExpr::Return(Box::new(value))]
+        };
+
+        JMember::MethodImpl(JMethodImpl {
+            args,
+            generics: BTreeMap::new(),
+            is_override: self.interface.is_some(),
+            is_suspend: false,
+            is_operator: false,
+            is_static: self.is_static,
+            name,
+            private: false,
+            ret: self.ret.j_type(),
+            code,
+        })
+    }
 }
@@ -20,6 +20,15 @@ pub struct Generator {
 
     /// The output bindings (Java) directory.
     pub out_dir: PathBuf,
+
+    /// Whether natives record `tracing` spans and call stats.
+    pub instrument: bool,
+
+    /// Whether the objects made for Java are tracked.
+    pub track_leaks: bool,
+
+    /// Whether the `NativeLogging` class is generated.
+    pub logging: bool,
 }
 
 impl Generator {
@@ -0,0 +1,75 @@
+//! Java interfaces, for trait `impl` blocks.
+
+use anyhow::{Result, bail};
+
+use crate::class::{Class, ctx::ClassCtx};
+
+use super::{
+    cx::Generator,
+    java::{JInterface, JMember, JMethodImpl},
+};
+
+/// Collect the Java interfaces for every trait `impl` block. Every class
+/// implementing a trait has to agree on its methods.
+pub fn collect_interfaces(cx: &Generator, classes: &[Class]) -> Result<Vec<JInterface>> {
+    let mut interfaces: Vec<(String, JInterface)> = Vec::new();
+
+    for class in classes {
+        let ccx = ClassCtx::new(class);
+
+        for imp in class.impls.iter().filter(|it| it.methods.is_some()) {
+            let name = imp.name();
+
+            let methods = class
+                .methods
+                .iter()
+                .filter(|it| it.interface.as_ref() == Some(&name))
+                .filter(|it| !it.is_static && !it.is_init)
+                .filter_map(|it| match it.wrapper_java_code(&ccx, cx) {
+                    JMember::MethodImpl(it) => Some(JMethodImpl {
+                        is_override: false,
+                        code: Vec::new(),
+                        ..it
+                    }),
+
+                    _ => None,
+                })
+                .collect::<Vec<_>>();
+
+            let interface = JInterface {
+                pkg: class.package.clone(),
+                name: name.clone(),
+                generics: imp.interface_generics(&class.generics),
+                methods,
+                imports: class.java_imports(cx),
+            };
+
+            match interfaces.iter().find(|(_, it)| it.name == name) {
+                Some((other, it)) => {
+                    if signatures(cx, it) != signatures(cx, &interface) {
+                        bail!(
+                            "Classes {other} and {} implement trait {name} with different methods!",
+                            class.name
+                        );
+                    }
+                }
+
+                None => interfaces.push((class.name.clone(), interface)),
+            }
+        }
+    }
+
+    Ok(interfaces.into_iter().map(|(_, it)| it).collect())
+}
+
+/// Get the generics and method signatures of an interface, for comparing them.
+fn signatures(cx: &Generator, interface: &JInterface) -> (Vec<String>, Vec<String>) {
+    (
+        interface.generics.clone(),
+        interface
+            .methods
+            .iter()
+            .map(|it| it.signature(cx))
+            .collect(),
+    )
+}
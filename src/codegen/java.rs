@@ -11,7 +11,11 @@ use crate::{
     loader::generate_loader,
 };
 
-use super::cx::Generator;
+use super::{
+    cx::Generator,
+    interface::collect_interfaces,
+    record::{collect_tuples, tuple_java_code},
+};
 
 /// Generate the Java code for an entire `.rs4j` file.
 pub fn gen_java_code(cx: &Generator, classes: &Vec<Class>) -> Result<()> {
@@ -22,6 +26,19 @@ pub fn gen_java_code(cx: &Generator, classes: &Vec<Class>) -> Result<()> {
         )?;
     }
 
+    for interface in collect_interfaces(cx, classes)? {
+        cx.emit(
+            format!("{}.{}", cx.package, interface.name),
+            interface.code(cx),
+        )?;
+    }
+
+    for tuple in collect_tuples(classes) {
+        if let Some(code) = tuple_java_code(cx, &tuple) {
+            cx.emit(format!("{}.{}", cx.package, tuple.kind.tuple_name()), code)?;
+        }
+    }
+
     cx.emit(format!("{}.NativeLoader", cx.package), generate_loader(&cx))?;
 
     cx.emit(
@@ -60,6 +77,48 @@ pub fn gen_java_code(cx: &Generator, classes: &Vec<Class>) -> Result<()> {
         },
     )?;
 
+    cx.emit(
+        "org.stardustmodding.rs4j.util.NativeIterator",
+        if cx.kotlin {
+            kotlin::NATIVE_ITERATOR
+        } else {
+            java::NATIVE_ITERATOR
+        },
+    )?;
+
+    if cx.logging {
+        cx.emit(
+            "org.stardustmodding.rs4j.util.NativeLogging",
+            if cx.kotlin {
+                kotlin::NATIVE_LOGGING
+            } else {
+                java::NATIVE_LOGGING
+            },
+        )?;
+    }
+
+    if cx.instrument {
+        cx.emit(
+            "org.stardustmodding.rs4j.util.NativeStats",
+            if cx.kotlin {
+                kotlin::NATIVE_STATS
+            } else {
+                java::NATIVE_STATS
+            },
+        )?;
+    }
+
+    if cx.track_leaks {
+        cx.emit(
+            "org.stardustmodding.rs4j.util.NativeLeaks",
+            if cx.kotlin {
+                kotlin::NATIVE_LEAKS
+            } else {
+                java::NATIVE_LEAKS
+            },
+        )?;
+    }
+
     Ok(())
 }
 
@@ -78,10 +137,25 @@ pub enum JType {
     Custom(String),
     Nullable(Box<JType>),
 
+    /// The boxed (object) form of a type, for use as a generic parameter.
+    Boxed(Box<JType>),
+
     Generic {
         base: Box<JType>,
         params: Vec<JType>,
     },
+
+    /// A generated tuple record. Kotlin uses `Pair` and `Triple` where it can.
+    Tuple {
+        name: String,
+        items: Vec<JType>,
+    },
+
+    /// An array. Kotlin uses its own classes for arrays of primitives.
+    Array(Box<JType>),
+
+    /// The last parameter of a varargs method.
+    Varargs(Box<JType>),
 }
 
 impl JType {
@@ -109,6 +183,52 @@ impl JType {
                     .join(", ")
             ),
 
+            JType::Tuple { name, items } => {
+                let base = match items.len() {
+                    2 if cx.kotlin => "Pair",
+                    3 if cx.kotlin => "Triple",
+                    _ => return name.clone(),
+                };
+
+                format!(
+                    "{base}<{}>",
+                    items
+                        .iter()
+                        .map(|it| JType::Boxed(Box::new(it.clone())).name(cx))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )
+            }
+
+            JType::Array(it) if cx.kotlin => match it.as_ref() {
+                JType::Int
+                | JType::Float
+                | JType::Long
+                | JType::Double
+                | JType::Bool
+                | JType::Byte
+                | JType::Short
+                | JType::Char => format!("{}Array", it.name(cx)),
+                _ => format!("Array<out {}>", it.name(cx)),
+            },
+
+            JType::Array(it) => format!("{}[]", it.name(cx)),
+            JType::Varargs(it) if cx.kotlin => it.name(cx),
+            JType::Varargs(it) => format!("{}...", it.name(cx)),
+
+            JType::Boxed(it) => match it.as_ref() {
+                JType::Int => if cx.kotlin { "Int" } else { "Integer" }.into(),
+                JType::Float => "Float".into(),
+                JType::Long => "Long".into(),
+                JType::Double => "Double".into(),
+                JType::Bool => "Boolean".into(),
+                JType::Void => if cx.kotlin { "Void?" } else { "Void" }.into(),
+                JType::Byte => "Byte".into(),
+                JType::Short => "Short".into(),
+                JType::Char => if cx.kotlin { "Char" } else { "Character" }.into(),
+                _ => it.name(cx),
+            },
+
             JType::Nullable(it) => {
                 if cx.kotlin {
                     format!("{}?", it.name(cx))
@@ -120,6 +240,51 @@ impl JType {
             }
         }
     }
+
+    /// Get a parameter of this type.
+    pub fn param(&self, name: &str, cx: &Generator) -> String {
+        match self {
+            JType::Varargs(_) if cx.kotlin => format!("vararg {name}: {}", self.name(cx)),
+            _ if cx.kotlin => format!("{name}: {}", self.name(cx)),
+            _ => format!("{} {name}", self.name(cx)),
+        }
+    }
+
+    /// Get the JVM type descriptor of this type, like `I` or `Ljava/lang/String;`.
+    /// Custom types need their qualified name for this.
+    pub fn descriptor(&self) -> String {
+        match self {
+            JType::Int => "I".into(),
+            JType::Float => "F".into(),
+            JType::Long => "J".into(),
+            JType::Double => "D".into(),
+            JType::Bool => "Z".into(),
+            JType::Void => "V".into(),
+            JType::Byte => "B".into(),
+            JType::Short => "S".into(),
+            JType::Char => "C".into(),
+            JType::String => "Ljava/lang/String;".into(),
+            JType::Custom(it) | JType::Tuple { name: it, .. } => {
+                format!("L{};", it.replace('.', "/"))
+            }
+            JType::Nullable(it) => it.descriptor(),
+            JType::Generic { base, .. } => base.descriptor(),
+            JType::Array(it) | JType::Varargs(it) => format!("[{}", it.descriptor()),
+
+            JType::Boxed(it) => match it.as_ref() {
+                JType::Int => "Ljava/lang/Integer;".into(),
+                JType::Float => "Ljava/lang/Float;".into(),
+                JType::Long => "Ljava/lang/Long;".into(),
+                JType::Double => "Ljava/lang/Double;".into(),
+                JType::Bool => "Ljava/lang/Boolean;".into(),
+                JType::Void => "Ljava/lang/Void;".into(),
+                JType::Byte => "Ljava/lang/Byte;".into(),
+                JType::Short => "Ljava/lang/Short;".into(),
+                JType::Char => "Ljava/lang/Character;".into(),
+                _ => it.descriptor(),
+            },
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -179,20 +344,41 @@ pub struct JMethodImpl {
     pub code: Vec<JExpr>,
     pub is_override: bool,
 
+    /// Is this a Kotlin `suspend fun`? Ignored for Java.
+    pub is_suspend: bool,
+
+    /// Is this a Kotlin `operator fun`? Ignored for Java.
+    pub is_operator: bool,
+
     /// A map of generic names to bounds.
     pub generics: BTreeMap<String, Vec<String>>,
 }
 
 impl JMethodImpl {
     pub fn code(&self, cx: &Generator) -> String {
+        let body = self
+            .code
+            .iter()
+            .map(|it| it.code(cx).ensure_semi(!cx.kotlin))
+            .collect::<Vec<_>>()
+            .join("\n")
+            .indent(4);
+
+        format!("{} {{\n{body}\n}}", self.signature(cx))
+    }
+
+    /// Get the signature of this method, without its body.
+    pub fn signature(&self, cx: &Generator) -> String {
         let JMethodImpl {
             name,
             ret,
             args,
             private,
             is_static,
-            code,
+            code: _,
             is_override,
+            is_suspend,
+            is_operator,
             generics,
         } = self;
 
@@ -201,13 +387,8 @@ impl JMethodImpl {
         let private_j = if_else!(*private, "private ", "public ");
         let private = if_else!(*private, "private ", "");
         let static_ = if_else!(*is_static, "static ", "");
-
-        let body = code
-            .iter()
-            .map(|it| it.code(cx).ensure_semi(!cx.kotlin))
-            .collect::<Vec<_>>()
-            .join("\n")
-            .indent(4);
+        let suspend = if_else!(*is_suspend, "suspend ", "");
+        let operator = if_else!(*is_operator, "operator ", "");
 
         if cx.kotlin {
             let wheres = generics
@@ -233,9 +414,9 @@ impl JMethodImpl {
             };
 
             format!(
-                "{private}{ov}fun{generics} {name}({}): {}{wheres} {{\n{body}\n}}",
+                "{private}{ov}{suspend}{operator}fun{generics} {name}({}): {}{wheres}",
                 args.iter()
-                    .map(|(name, ty)| format!("{name}: {}", ty.name(cx)))
+                    .map(|(name, ty)| ty.param(name, cx))
                     .collect::<Vec<_>>()
                     .join(", "),
                 ret.name(cx),
@@ -247,7 +428,7 @@ impl JMethodImpl {
                     if bounds.is_empty() {
                         name.clone()
                     } else {
-                        format!("{} extends {}", name, bounds.join(" + "))
+                        format!("{} extends {}", name, bounds.join(" & "))
                     }
                 })
                 .collect::<Vec<_>>();
@@ -259,10 +440,10 @@ impl JMethodImpl {
             };
 
             format!(
-                "{ov_j}{private_j}{static_}{generics}{} {name}({}) {{\n{body}\n}}",
+                "{ov_j}{private_j}{static_}{generics}{} {name}({})",
                 ret.name(cx),
                 args.iter()
-                    .map(|(name, ty)| format!("{} {name}", ty.name(cx)))
+                    .map(|(name, ty)| ty.param(name, cx))
                     .collect::<Vec<_>>()
                     .join(", "),
             )
@@ -340,8 +521,15 @@ impl JGetterImpl {
                     ret.name(cx),
                 )
             } else {
+                // Overrides keep the name they're overriding.
+                let jvm_name = if_else!(
+                    *is_override,
+                    String::new(),
+                    format!("@get:JvmName(\"{name}\")\n")
+                );
+
                 format!(
-                    "{private}{ov}val {getter_name}: {} get() {{\n{body}\n}}",
+                    "{jvm_name}{private}{ov}val {getter_name}: {} get() {{\n{body}\n}}",
                     ret.name(cx),
                 )
             }
@@ -352,7 +540,7 @@ impl JGetterImpl {
                     if bounds.is_empty() {
                         name.clone()
                     } else {
-                        format!("{} extends {}", name, bounds.join(" + "))
+                        format!("{} extends {}", name, bounds.join(" & "))
                     }
                 })
                 .collect::<Vec<_>>();
@@ -491,9 +679,11 @@ impl JGetterSetterImpl {
                 code: set_code,
                 generics: BTreeMap::new(),
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: *is_static,
                 private: self.private,
-                ret: ty.j_type(),
+                ret: JType::Void,
                 name: self.setter_name.clone(),
             };
 
@@ -502,6 +692,8 @@ impl JGetterSetterImpl {
                 code: get_code,
                 generics: BTreeMap::new(),
                 is_override: false,
+                is_suspend: false,
+                is_operator: false,
                 is_static: *is_static,
                 private: self.private,
                 ret: ty.j_type(),
@@ -546,8 +738,11 @@ impl JField {
             "".into()
         };
 
+        // Static finals are only used for constants.
+        let const_ = if_else!(*is_static && *is_final, "const ", "");
+
         if cx.kotlin {
-            format!("{private}{final_}{name}: {}{value}", ty.name(cx),)
+            format!("{private}{const_}{final_}{name}: {}{value}", ty.name(cx),)
         } else {
             format!(
                 "{private_j}{static_}{final_j}{} {name}{value};",
@@ -588,7 +783,7 @@ impl JCtor {
             format!(
                 "{private}constructor({}) {{\n{body}\n}}",
                 args.iter()
-                    .map(|(name, ty)| format!("{name}: {}", ty.name(cx)))
+                    .map(|(name, ty)| ty.param(name, cx))
                     .collect::<Vec<_>>()
                     .join(", "),
             )
@@ -596,7 +791,7 @@ impl JCtor {
             format!(
                 "{private_j}{name}({}) {{\n{body}\n}}",
                 args.iter()
-                    .map(|(name, ty)| format!("{} {name}", ty.name(cx)))
+                    .map(|(name, ty)| ty.param(name, cx))
                     .collect::<Vec<_>>()
                     .join(", "),
             )
@@ -624,6 +819,28 @@ impl JCall {
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct JMethodCall {
+    pub target: Box<JExpr>,
+    pub name: String,
+    pub args: Vec<JExpr>,
+}
+
+impl JMethodCall {
+    pub fn code(&self, cx: &Generator) -> String {
+        format!(
+            "{}.{}({})",
+            self.target.code(cx),
+            self.name,
+            self.args
+                .iter()
+                .map(|it| it.code(cx))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct JSafeFieldCall {
     pub field: String,
@@ -709,7 +926,7 @@ impl JIf {
             self.cond.code(cx),
             self.body
                 .iter()
-                .map(|it| it.code(cx))
+                .map(|it| it.code(cx).ensure_semi(!cx.kotlin))
                 .collect::<Vec<_>>()
                 .join("\n")
                 .indent(4)
@@ -750,6 +967,7 @@ impl JVar {
 #[derive(Debug, Clone)]
 pub enum JExpr {
     Call(JCall),
+    MethodCall(JMethodCall),
     SetField(JSetField),
     Name(String),
     If(JIf),
@@ -764,6 +982,7 @@ impl JExpr {
     pub fn code(&self, cx: &Generator) -> String {
         match self {
             JExpr::Call(it) => it.code(cx),
+            JExpr::MethodCall(it) => it.code(cx),
             JExpr::New(it) => it.code(cx),
             JExpr::SetField(it) => it.code(cx),
             JExpr::If(it) => it.code(cx),
@@ -791,6 +1010,7 @@ pub enum JMember {
     Ctor(JCtor),
     Getter(JGetterImpl),
     GetterSetter(JGetterSetterImpl),
+    Nested(JNested),
 }
 
 impl JMember {
@@ -802,10 +1022,37 @@ impl JMember {
             JMember::Ctor(it) => it.code(cx),
             JMember::Getter(it) => it.code(cx),
             JMember::GetterSetter(it) => it.code(cx),
+            JMember::Nested(it) => it.code(cx),
         }
     }
 }
 
+/// A private nested class (Kotlin: `object`) holding static members.
+#[derive(Debug, Clone)]
+pub struct JNested {
+    pub name: String,
+    pub members: Vec<JMember>,
+}
+
+impl JNested {
+    pub fn code(&self, cx: &Generator) -> String {
+        let head = if_else!(
+            cx.kotlin,
+            format!("private object {} {{", self.name),
+            format!("private static final class {} {{", self.name)
+        );
+
+        let members = self
+            .members
+            .iter()
+            .map(|it| it.code(cx).indent(4))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!("{head}\n{members}\n}}")
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct JClassDef {
     pub pkg: String,
@@ -814,6 +1061,9 @@ pub struct JClassDef {
     pub members: Vec<JMember>,
     pub imports: Vec<String>,
     pub wheres: String,
+
+    /// The components, if this is a `record` (Kotlin: `data class`).
+    pub record: Option<Vec<(String, JType)>>,
 }
 
 impl JClassDef {
@@ -839,7 +1089,20 @@ impl JClassDef {
                 format!(" : {}", self.extends.join(", "))
             };
 
-            data.push(format!("class {}{ext}{} {{", self.name, self.wheres));
+            if let Some(components) = &self.record {
+                let components = components
+                    .iter()
+                    .map(|(name, ty)| format!("val {name}: {}", ty.name(cx)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                data.push(format!(
+                    "data class {}({components}){ext}{} {{",
+                    self.name, self.wheres
+                ));
+            } else {
+                data.push(format!("class {}{ext}{} {{", self.name, self.wheres));
+            }
 
             let mut companion = Vec::new();
 
@@ -888,7 +1151,17 @@ impl JClassDef {
                 format!(" implements {}", self.extends.join(", "))
             };
 
-            data.push(format!("public class {}{ext} {{", self.name));
+            if let Some(components) = &self.record {
+                let components = components
+                    .iter()
+                    .map(|(name, ty)| format!("{} {name}", ty.name(cx)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                data.push(format!("public record {}({components}){ext} {{", self.name));
+            } else {
+                data.push(format!("public class {}{ext} {{", self.name));
+            }
 
             for item in &self.members {
                 data.push(item.code(cx).indent(4));
@@ -900,6 +1173,57 @@ impl JClassDef {
     }
 }
 
+/// A Java interface, for a trait.
+#[derive(Debug, Clone)]
+pub struct JInterface {
+    pub pkg: String,
+    pub name: String,
+    pub generics: Vec<String>,
+    pub methods: Vec<JMethodImpl>,
+    pub imports: Vec<String>,
+}
+
+impl JInterface {
+    pub fn code(&self, cx: &Generator) -> String {
+        let mut data = Vec::new();
+        let semi = if_else!(cx.kotlin, "", ";");
+
+        if cx.kotlin {
+            data.push("@file:Suppress(\"FunctionName\", \"unused\", \"RedundantUnitReturnType\", \"UnusedImport\")".into());
+        }
+
+        data.push(format!("package {}{semi}", self.pkg));
+        data.push("".into());
+
+        if !self.imports.is_empty() {
+            for item in &self.imports {
+                data.push(format!("import {item}{semi}"));
+            }
+
+            data.push("".into());
+        }
+
+        let generics = if_else!(
+            self.generics.is_empty(),
+            String::new(),
+            format!("<{}>", self.generics.join(", "))
+        );
+
+        data.push(format!(
+            "{}interface {}{generics} {{",
+            if_else!(cx.kotlin, "", "public "),
+            self.name
+        ));
+
+        for method in &self.methods {
+            data.push(format!("{}{semi}", method.signature(cx)).indent(4));
+        }
+
+        data.push("}".into());
+        data.join("\n")
+    }
+}
+
 pub trait JUtil {
     fn indent(&self, amount: usize) -> String;
     fn ensure_semi(self, semi: bool) -> String;
@@ -917,7 +1241,7 @@ impl JUtil for String {
     }
 
     fn ensure_semi(self, semi: bool) -> String {
-        if semi && !self.ends_with(';') {
+        if semi && !self.ends_with(';') && !self.ends_with('}') {
             format!("{self};")
         } else {
             self
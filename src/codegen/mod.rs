@@ -1,5 +1,7 @@
 //! The codegen module.
 
 pub mod cx;
+pub mod interface;
 pub mod java;
+pub mod record;
 pub mod rust;
@@ -0,0 +1,476 @@
+//! Records, for tuples and value classes.
+//!
+//! Every distinct tuple shape gets a generated Java `record` (or a Kotlin
+//! `data class`, using `Pair` and `Triple` where it can), and so does every
+//! `value class`. Each one gets a pair of Rust functions copying values
+//! across in both directions, in a local reference frame, so the objects made
+//! for components along the way are freed.
+
+use convert_case::{Case, Casing};
+
+use crate::{
+    class::{
+        Class,
+        base::RUST_BRIDGE_HEAD_MANGLE,
+        ty::{Type, TypeKind},
+    },
+    if_else,
+};
+
+use super::{
+    cx::Generator,
+    java::{JClassDef, JType},
+};
+
+/// The names of a tuple record's components.
+const COMPONENTS: [&str; 12] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+    "eleventh", "twelfth",
+];
+
+/// Collect every tuple type used by the classes, including nested ones.
+pub fn collect_tuples(classes: &Vec<Class>) -> Vec<Type> {
+    let mut tuples = Vec::new();
+
+    for class in classes {
+        for field in &class.fields {
+            visit(&field.ty, &mut tuples);
+        }
+
+        for method in &class.methods {
+            visit(&method.ret, &mut tuples);
+
+            for arg in &method.args {
+                visit(&arg.ty, &mut tuples);
+            }
+        }
+    }
+
+    tuples
+}
+
+fn visit(ty: &Type, tuples: &mut Vec<Type>) {
+    for it in ty.generics.iter().flatten() {
+        visit(it, tuples);
+    }
+
+    if let TypeKind::Tuple(items) = &ty.kind {
+        for it in items {
+            visit(it, tuples);
+        }
+
+        let ty = Type::new(ty.kind.clone(), None);
+
+        if !tuples.contains(&ty) {
+            tuples.push(ty);
+        }
+    }
+}
+
+/// Get the items of a tuple type.
+fn items(ty: &Type) -> &Vec<Type> {
+    match &ty.kind {
+        TypeKind::Tuple(items) => items,
+        _ => panic!("Not a tuple: {:?}", ty),
+    }
+}
+
+/// Does this tuple use Kotlin's `Pair` or `Triple` instead of a generated class?
+fn is_builtin(cx: &Generator, ty: &Type) -> bool {
+    cx.kotlin && matches!(items(ty).len(), 2 | 3)
+}
+
+/// Get the JVM class name of a tuple.
+fn class_path(cx: &Generator, ty: &Type) -> String {
+    match items(ty).len() {
+        2 if cx.kotlin => "kotlin/Pair".into(),
+        3 if cx.kotlin => "kotlin/Triple".into(),
+        _ => format!("{}/{}", cx.dir_pkg(), ty.kind.tuple_name()),
+    }
+}
+
+/// Get the JVM type descriptor of a type.
+fn descriptor(cx: &Generator, ty: &Type) -> String {
+    match &ty.kind {
+        TypeKind::Void => "V".into(),
+        TypeKind::String => "Ljava/lang/String;".into(),
+        TypeKind::I8 | TypeKind::U8 => "B".into(),
+        TypeKind::I16 | TypeKind::U16 => "S".into(),
+        TypeKind::I32 | TypeKind::U32 => "I".into(),
+        TypeKind::I64 | TypeKind::U64 => "J".into(),
+        TypeKind::F32 => "F".into(),
+        TypeKind::F64 => "D".into(),
+        TypeKind::Bool => "Z".into(),
+        TypeKind::Char => "C".into(),
+        TypeKind::Duration => "Ljava/time/Duration;".into(),
+        TypeKind::SystemTime => "Ljava/time/Instant;".into(),
+        TypeKind::PathBuf => "Ljava/nio/file/Path;".into(),
+        TypeKind::Iter => "Lorg/stardustmodding/rs4j/util/NativeIterator;".into(),
+        TypeKind::Slice(item) => format!("[{}", descriptor(cx, item)),
+        TypeKind::Tuple(_) => format!("L{};", class_path(cx, ty)),
+        TypeKind::Value(it) | TypeKind::Other(it) => format!("L{}/{};", cx.dir_pkg(), it),
+    }
+}
+
+/// Get the `JValue` and `Primitive` variants for a primitive type.
+fn primitive(ty: &Type) -> Option<(&'static str, &'static str)> {
+    match &ty.kind {
+        TypeKind::I8 | TypeKind::U8 => Some(("Byte", "Byte")),
+        TypeKind::I16 | TypeKind::U16 => Some(("Short", "Short")),
+        TypeKind::I32 | TypeKind::U32 => Some(("Int", "Int")),
+        TypeKind::I64 | TypeKind::U64 => Some(("Long", "Long")),
+        TypeKind::F32 => Some(("Float", "Float")),
+        TypeKind::F64 => Some(("Double", "Double")),
+        TypeKind::Bool => Some(("Bool", "Boolean")),
+        TypeKind::Char => Some(("Char", "Char")),
+        _ => None,
+    }
+}
+
+/// Generate an expression converting a tuple item into a `JValueOwned`.
+fn item_to_jvalue(cx: &Generator, var: &str, ty: &Type, boxed: bool) -> String {
+    if let Some((variant, _)) = primitive(ty) {
+        let raw = match ty.kind {
+            TypeKind::F32 | TypeKind::F64 => var.to_string(),
+            TypeKind::Bool => format!("{var} as u8"),
+            TypeKind::Char => format!("{var} as u16"),
+            _ => format!("{var} as {}", primitive_cast(ty)),
+        };
+
+        return if_else!(
+            boxed,
+            format!(
+                "JValueOwned::Object(JObject::from_raw(jvalue_to_jobject(env, JValue::{variant}({raw}))))"
+            ),
+            format!("JValueOwned::{variant}({raw})")
+        );
+    }
+
+    let obj = match &ty.kind {
+        TypeKind::String => format!("env.new_string({var}).unwrap().into()"),
+
+        TypeKind::Other(_) => format!(
+            "JObject::from_raw(object_to_jobject(env, __JNI_{}::of({var}), \"{}/{}\"))",
+            ty.kind.rust_name(),
+            cx.dir_pkg(),
+            ty.kind.rust_name(),
+        ),
+
+        _ => match ty.kind.to_jobject_func() {
+            Some(func) if ty.kind.is_std_object() => {
+                format!("JObject::from_raw({func}(env, {var})?)")
+            }
+            Some(func) => format!("JObject::from_raw({func}(env, {var}))"),
+            None => "JObject::null()".into(),
+        },
+    };
+
+    format!("JValueOwned::Object({obj})")
+}
+
+/// Generate a `CachedMethod` for a record component's getter.
+fn getter(cx: &Generator, name: &str, ty: &Type, boxed: bool) -> String {
+    let getter = if_else!(
+        cx.kotlin,
+        format!("get{}{}", name[..1].to_uppercase(), &name[1..]),
+        name.to_string()
+    );
+
+    let desc = if_else!(boxed, "Ljava/lang/Object;".to_string(), descriptor(cx, ty));
+
+    format!("CachedMethod::new(\"{getter}\", \"(){desc}\")")
+}
+
+/// Generate an expression reading a record component from a Java object, with
+/// the getter in `GETTERS[index]`.
+fn item_from_jobject(index: usize, ty: &Type, boxed: bool) -> String {
+    let ret = match primitive(ty) {
+        Some((_, prim)) if !boxed => format!("ReturnType::Primitive(Primitive::{prim})"),
+        _ => "ReturnType::Object".into(),
+    };
+
+    let get = format!(
+        "let id = GETTERS[{index}].get(env, &class)?; let it = env.call_method_unchecked(val, id, {ret}, &[])?;"
+    );
+
+    if let Some((_, prim)) = primitive(ty) {
+        let value = if_else!(
+            boxed,
+            format!("{{ {get} jobject_to_jvalue(env, &it.l()?, Primitive::{prim}) }}"),
+            format!("{{ {get} it }}")
+        );
+
+        return match ty.kind {
+            TypeKind::Bool => format!("{value}.z().unwrap()"),
+            TypeKind::Char => {
+                format!("char::from_u32({value}.c().unwrap() as u32).unwrap_or_default()")
+            }
+            TypeKind::F32 => format!("{value}.f().unwrap()"),
+            TypeKind::F64 => format!("{value}.d().unwrap()"),
+            TypeKind::I8 | TypeKind::U8 => {
+                format!("{value}.b().unwrap() as {}", ty.kind.rust_name())
+            }
+            TypeKind::I16 | TypeKind::U16 => {
+                format!("{value}.s().unwrap() as {}", ty.kind.rust_name())
+            }
+            TypeKind::I32 | TypeKind::U32 => {
+                format!("{value}.i().unwrap() as {}", ty.kind.rust_name())
+            }
+            _ => format!("{value}.j().unwrap() as {}", ty.kind.rust_name()),
+        };
+    }
+
+    let conv = match &ty.kind {
+        TypeKind::Void => "()".into(),
+
+        TypeKind::String => {
+            "env.get_string(&JString::from(it)).unwrap().to_str().unwrap().to_string()".into()
+        }
+
+        TypeKind::Other(_) => format!(
+            "(*(object_pointer(env, &it) as *mut __JNI_{})).to_rust()",
+            ty.full_type()
+        ),
+
+        _ => match ty.kind.from_jobject_func() {
+            Some(func) if ty.kind.is_std_object() => format!("{func}(env, &it)?"),
+            Some(func) => format!("{func}(env, &it)"),
+            None => "Default::default()".into(),
+        },
+    };
+
+    format!("{{ {get} let it = it.l()?; {conv} }}")
+}
+
+/// Get the JNI integer type a primitive is cast through.
+fn primitive_cast(ty: &Type) -> &'static str {
+    match ty.kind {
+        TypeKind::I8 | TypeKind::U8 => "i8",
+        TypeKind::I16 | TypeKind::U16 => "i16",
+        TypeKind::I32 | TypeKind::U32 => "i32",
+        _ => "i64",
+    }
+}
+
+/// Generate the Java (or Kotlin) record for a tuple, if it needs one.
+pub fn tuple_java_code(cx: &Generator, ty: &Type) -> Option<String> {
+    if is_builtin(cx, ty) {
+        return None;
+    }
+
+    let components = items(ty)
+        .iter()
+        .zip(COMPONENTS)
+        .map(|(it, name)| (name.to_string(), it.j_type()))
+        .collect::<Vec<_>>();
+
+    let record = JClassDef {
+        pkg: cx.package.clone(),
+        name: ty.kind.tuple_name(),
+        extends: Vec::new(),
+        members: Vec::new(),
+        imports: Vec::new(),
+        wheres: String::new(),
+        record: Some(components),
+    };
+
+    Some(record.code(cx))
+}
+
+/// Get the components of a value class's record.
+pub fn value_components(class: &Class) -> Vec<(String, JType)> {
+    class
+        .fields
+        .iter()
+        .filter(|it| !it.rust)
+        .map(|it| (it.name.to_case(Case::Camel), it.ty.j_type()))
+        .collect()
+}
+
+/// A record crossing the bridge, and how to take apart and build its Rust value.
+struct Record<'a> {
+    /// The Rust type.
+    rust: String,
+
+    /// The function converting the value into a Java object.
+    to: String,
+
+    /// The function converting a Java object into the value.
+    from: String,
+
+    /// The JVM class name.
+    class: String,
+
+    /// Are the record's components erased to `Object`?
+    boxed: bool,
+
+    /// The component names and their types.
+    components: Vec<(String, &'a Type)>,
+
+    /// A pattern binding each component to `v0`, `v1`, ...
+    destructure: String,
+
+    /// An expression building the value from `v0`, `v1`, ...
+    construct: String,
+}
+
+/// Generate the Rust functions converting a record to and from Java.
+fn record_rust_code(cx: &Generator, record: Record) -> String {
+    let Record {
+        rust,
+        to,
+        from,
+        class,
+        boxed,
+        components,
+        destructure,
+        construct,
+    } = record;
+
+    let sig = components
+        .iter()
+        .map(|(_, it)| if_else!(boxed, "Ljava/lang/Object;".into(), descriptor(cx, it)))
+        .collect::<String>();
+
+    let to_items = components
+        .iter()
+        .enumerate()
+        .map(|(i, (_, it))| {
+            format!(
+                "        let v{i} = {};",
+                item_to_jvalue(cx, &format!("v{i}"), it, boxed)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let from_items = components
+        .iter()
+        .enumerate()
+        .map(|(i, (_, it))| format!("        let v{i} = {};", item_from_jobject(i, it, boxed)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let getters = components
+        .iter()
+        .map(|(name, it)| format!("        {},", getter(cx, name, it, boxed)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let count = components.len();
+
+    let args = (0..count)
+        .map(|i| format!("v{i}.borrow().as_jni()"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    format!(
+        "{RUST_BRIDGE_HEAD_MANGLE}
+pub unsafe fn {to}(env: &mut JNIEnv, val: {rust}) -> jobject {{
+    static CLASS: CachedClass = CachedClass::new(\"{class}\");
+    static NEW: CachedMethod = CachedMethod::new(\"<init>\", \"({sig})V\");
+
+    let {destructure} = val;
+
+    env.with_local_frame_returning_local(frame_capacity(), |env| {{
+{to_items}
+
+        let class = CLASS.get(env)?;
+        let new = NEW.get(env, &class)?;
+
+        env.new_object_unchecked(<&JClass>::from(class.as_obj()), new, &[{args}])
+    }})
+    .unwrap()
+    .into_raw()
+}}
+
+{RUST_BRIDGE_HEAD_MANGLE}
+pub unsafe fn {from}(env: &mut JNIEnv, val: &JObject) -> {rust} {{
+    static CLASS: CachedClass = CachedClass::new(\"{class}\");
+    static GETTERS: [CachedMethod; {count}] = [
+{getters}
+    ];
+
+    env.with_local_frame(frame_capacity(), |env| -> errors::Result<{rust}> {{
+        let class = CLASS.get(env)?;
+
+{from_items}
+
+        Ok({construct})
+    }})
+    .unwrap()
+}}"
+    )
+}
+
+/// Generate the Rust functions converting a tuple to and from its record.
+pub fn tuple_rust_code(cx: &Generator, ty: &Type) -> String {
+    let items = items(ty);
+
+    let vars = (0..items.len())
+        .map(|i| format!("v{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    record_rust_code(
+        cx,
+        Record {
+            rust: ty.kind.rust_name(),
+            to: ty.kind.to_jobject_func().unwrap(),
+            from: ty.kind.from_jobject_func().unwrap(),
+            class: class_path(cx, ty),
+            boxed: is_builtin(cx, ty),
+            components: COMPONENTS
+                .iter()
+                .map(|it| it.to_string())
+                .zip(items)
+                .collect(),
+            destructure: format!("({vars})"),
+            construct: format!("({vars})"),
+        },
+    )
+}
+
+/// Generate the Rust functions converting a value class to and from its record.
+/// Rust-only fields are skipped, and filled in with their defaults.
+pub fn value_rust_code(cx: &Generator, class: &Class) -> String {
+    let kind = TypeKind::Value(class.name.clone());
+    let fields = class
+        .fields
+        .iter()
+        .filter(|it| !it.rust)
+        .collect::<Vec<_>>();
+
+    let destructure = fields
+        .iter()
+        .enumerate()
+        .map(|(i, it)| format!("{}: v{i}, ", it.name))
+        .collect::<String>();
+
+    let mut construct = fields
+        .iter()
+        .enumerate()
+        .map(|(i, it)| format!("{}: v{i}", it.name))
+        .collect::<Vec<_>>();
+
+    for field in class.fields.iter().filter(|it| it.rust) {
+        construct.push(format!("{}: Default::default()", field.name));
+    }
+
+    record_rust_code(
+        cx,
+        Record {
+            rust: class.name.clone(),
+            to: kind.to_jobject_func().unwrap(),
+            from: kind.from_jobject_func().unwrap(),
+            class: format!("{}/{}", cx.dir_pkg(), class.name),
+            boxed: false,
+            components: fields
+                .iter()
+                .map(|it| (it.name.to_case(Case::Camel), &it.ty))
+                .collect(),
+            destructure: format!("{} {{ {destructure}.. }}", class.name),
+            construct: format!("{} {{ {} }}", class.name, construct.join(", ")),
+        },
+    )
+}
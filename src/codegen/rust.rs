@@ -8,16 +8,36 @@ use std::{
 
 use anyhow::Result;
 
-use crate::class::Class;
+use crate::class::{Class, external::ExternClass};
 
-use super::cx::Generator;
+use super::{
+    cx::Generator,
+    record::{collect_tuples, tuple_rust_code, value_rust_code},
+};
 
 /// Generate Rust bindings and write them to a file.
-pub fn gen_rust_code(_cx: &Generator, classes: &Vec<Class>, out_file: &PathBuf) -> Result<()> {
+pub fn gen_rust_code(
+    cx: &Generator,
+    classes: &Vec<Class>,
+    externs: &[ExternClass],
+    out_file: &PathBuf,
+) -> Result<()> {
     let mut data = "use rs4j::prelude::*;\n\n".to_string();
 
     for class in classes {
-        data.push_str(&format!("{}\n\n", class.rust_code()));
+        data.push_str(&format!("{}\n\n", class.rust_code(cx)));
+    }
+
+    for class in classes.iter().filter(|it| it.value) {
+        data.push_str(&format!("{}\n\n", value_rust_code(cx, class)));
+    }
+
+    for tuple in collect_tuples(classes) {
+        data.push_str(&format!("{}\n\n", tuple_rust_code(cx, &tuple)));
+    }
+
+    for class in externs {
+        data.push_str(&format!("{}\n\n", class.rust_code(externs)?));
     }
 
     if !out_file.parent().unwrap().exists() {
@@ -0,0 +1,70 @@
+//! Conversions from Java arrays, for varargs and slice arguments.
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{JObject, JObjectArray, JPrimitiveArray, ReleaseMode, TypeArray},
+    signature::{Primitive, ReturnType},
+    sys::jlong,
+};
+
+use super::{
+    cache::{CachedClass, CachedMethod},
+    conv::check_non_null,
+    vm::frame_capacity,
+};
+
+static NATIVE_CLASS: CachedClass = CachedClass::new("org/stardustmodding/rs4j/util/NativeClass");
+static NATIVE_CLASS_POINTER: CachedMethod = CachedMethod::new("getPointer", "()J");
+
+/// Convert a Java object array into a [`Vec`], converting each element.
+/// If the array is `null`, or converting an element throws, this returns an
+/// [`Err`], leaving the exception pending.
+///
+/// Every element is converted in its own local reference frame (see
+/// [`frame_capacity`]), so large arrays, and conversions making their own
+/// references, don't fill up the local reference table.
+pub fn object_array_to_vec<T>(
+    env: &mut JNIEnv,
+    array: &JObjectArray,
+    mut convert: impl FnMut(&mut JNIEnv, &JObject) -> Result<T>,
+) -> Result<Vec<T>> {
+    check_non_null(env, array, "array")?;
+
+    let len = env.get_array_length(array)?;
+    let mut items = Vec::with_capacity(len as usize);
+
+    for i in 0..len {
+        let item = env.with_local_frame(frame_capacity(), |env| -> Result<T> {
+            let item = env.get_object_array_element(array, i)?;
+
+            convert(env, &item)
+        })?;
+
+        items.push(item);
+    }
+
+    Ok(items)
+}
+
+/// Copy a Java primitive array into a [`Vec`]. The array itself is never
+/// changed.
+pub fn primitive_array_to_vec<T: TypeArray + Copy>(
+    env: &mut JNIEnv,
+    array: &JPrimitiveArray<T>,
+) -> Vec<T> {
+    let items = unsafe { env.get_array_elements(array, ReleaseMode::NoCopyBack) }.unwrap();
+
+    items.to_vec()
+}
+
+/// Get the native pointer of an object of a generated class.
+pub fn object_pointer(env: &mut JNIEnv, obj: &JObject) -> jlong {
+    let class = NATIVE_CLASS.get(env).unwrap();
+    let pointer = NATIVE_CLASS_POINTER.get(env, &class).unwrap();
+
+    unsafe { env.call_method_unchecked(obj, pointer, ReturnType::Primitive(Primitive::Long), &[]) }
+        .unwrap()
+        .j()
+        .unwrap()
+}
@@ -1,12 +1,12 @@
 //! JNI functions for the NativeTools class
 
 use jni::{
-    objects::{JClass, JString},
+    objects::JClass,
     sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort, jstring},
     JNIEnv,
 };
 
-use super::include::object_to_jobject;
+use super::{include::pointer_to_instance, task::cancel_task};
 
 #[unsafe(no_mangle)]
 #[allow(
@@ -81,9 +81,26 @@ pub unsafe extern "system" fn Java_org_stardustmodding_rs4j_util_NativeTools_get
     mut env: JNIEnv<'local>,
     class: JClass<'local>,
     ptr: jlong,
-    cls: JString<'local>,
+    cls: JClass<'local>,
 ) -> jobject {
-    let cls = env.get_string(&cls).unwrap();
-    let cls = cls.to_str().unwrap();
-    object_to_jobject(env, ptr, cls.to_string())
+    pointer_to_instance(&mut env, &cls, ptr)
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeTools_cancelTask<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    task: jlong,
+) {
+    cancel_task(task as u64)
 }
@@ -0,0 +1,362 @@
+//! Caches for classes and member IDs, so they're only looked up by name once.
+//!
+//! Each cache is a `static`, which looks its class or member up the first time
+//! it's used. Everything is cleared by [`clear_cache`] (which
+//! [`on_unload`](crate::on_unload) calls), and looked up again if it's used
+//! afterwards.
+//!
+//! Classes are looked up with the class loader that loaded the library, which
+//! is remembered by the first native Java calls (see [`find_class`]), so
+//! threads Rust started find the same classes as Java.
+//!
+//! Classes (and that class loader) are held as [`GlobalRef`]s, so they can't
+//! be unloaded while they're cached. For the app's own classes, this also
+//! keeps their class loader from being collected, and with it the library, so
+//! the JVM won't call `JNI_OnUnload` by itself while they're cached. If the
+//! class loader should be collected, call [`clear_cache`] first.
+//!
+//! ```rust,ignore
+//! static LIST: CachedClass = CachedClass::new("java/util/ArrayList");
+//! static SIZE: CachedMethod = CachedMethod::new("size", "()I");
+//!
+//! let class = LIST.get(env)?;
+//! let size = SIZE.get(env, &class)?;
+//! ```
+
+use std::{
+    collections::HashMap,
+    sync::{
+        Mutex, Once, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{GlobalRef, JClass, JFieldID, JMethodID, JStaticFieldID, JStaticMethodID, JValue},
+};
+
+use super::call::global_ref;
+
+/// Every cache that's been filled.
+static CACHES: Mutex<Vec<&'static (dyn Clear + Sync)>> = Mutex::new(Vec::new());
+
+/// Something in [`CACHES`].
+trait Clear {
+    /// Forget what's cached.
+    fn clear(&self);
+}
+
+/// Add a cache to [`CACHES`], the first time it's filled.
+fn register(once: &Once, cache: &'static (dyn Clear + Sync)) {
+    once.call_once(|| CACHES.lock().unwrap().push(cache));
+}
+
+/// The class loader that loaded the library.
+static CLASS_LOADER: RwLock<Option<GlobalRef>> = RwLock::new(None);
+
+/// Whether [`CLASS_LOADER`] has been looked for, so natives can check without
+/// locking it.
+static CLASS_LOADER_SEARCHED: AtomicBool = AtomicBool::new(false);
+
+/// A class that's generated next to the app's classes, so it's loaded by the
+/// same class loader.
+const LOADER_CLASS: &str = "org/stardustmodding/rs4j/util/NativeClass";
+
+/// Clear every cache, dropping the classes they hold and the class loader.
+/// Anything used after this is looked up again.
+pub fn clear_cache() {
+    for cache in CACHES.lock().unwrap().iter() {
+        cache.clear();
+    }
+
+    CLASS_LOADER.write().unwrap().take();
+    CLASS_LOADER_SEARCHED.store(false, Ordering::Release);
+}
+
+/// Remember the class loader that loaded the library, if it hasn't been
+/// looked for yet. This has to run on a thread Java called into (or in
+/// `JNI_OnLoad`), where `FindClass` uses that class loader. Every generated
+/// native calls this through [`remember_java_vm`](super::vm::remember_java_vm),
+/// so after the first call, it's only an atomic load.
+pub fn remember_class_loader(env: &mut JNIEnv) {
+    if CLASS_LOADER_SEARCHED.load(Ordering::Acquire) {
+        return;
+    }
+
+    // JNI can't be used with an exception pending, and it isn't ours to clear.
+    // Leave it for Java, and look again next time.
+    if env.exception_check().unwrap_or(true) {
+        return;
+    }
+
+    let loader = env.with_local_frame(4, |env| -> Result<Option<GlobalRef>> {
+        let class = env.find_class(LOADER_CLASS)?;
+
+        let loader = env
+            .call_method(&class, "getClassLoader", "()Ljava/lang/ClassLoader;", &[])?
+            .l()?;
+
+        // Classes from the bootstrap class loader don't have one.
+        if loader.is_null() {
+            return Ok(None);
+        }
+
+        Ok(Some(env.new_global_ref(loader)?))
+    });
+
+    match loader {
+        Ok(Some(loader)) => {
+            CLASS_LOADER.write().unwrap().get_or_insert(loader);
+        }
+
+        Ok(None) => {}
+
+        // Without the generated classes, there's nothing to find anyway. Any
+        // exception was thrown by the lookup, since none was pending before.
+        Err(err) => {
+            if env.exception_check().unwrap_or(false) {
+                let _ = env.exception_clear();
+            }
+
+            #[cfg(feature = "log")]
+            log::warn!("Couldn't find the class loader that loaded the library: {err}");
+
+            #[cfg(not(feature = "log"))]
+            let _ = err;
+        }
+    }
+
+    CLASS_LOADER_SEARCHED.store(true, Ordering::Release);
+}
+
+/// Look a class up by its JNI name (like `java/util/ArrayList`).
+///
+/// `FindClass` only uses the library's class loader on threads Java called
+/// into, and the system class loader everywhere else, which can't see the
+/// app's classes if they were loaded by another one. So once the class loader
+/// is known, classes are loaded through it instead, on any thread.
+pub fn find_class<'local>(env: &mut JNIEnv<'local>, name: &str) -> Result<JClass<'local>> {
+    let loader = CLASS_LOADER.read().unwrap().clone();
+
+    let Some(loader) = loader else {
+        return env.find_class(name);
+    };
+
+    let binary_name = env.new_string(name.replace('/', "."))?;
+
+    let class = env
+        .call_static_method(
+            "java/lang/Class",
+            "forName",
+            "(Ljava/lang/String;ZLjava/lang/ClassLoader;)Ljava/lang/Class;",
+            &[
+                JValue::Object(&binary_name),
+                JValue::Bool(1),
+                JValue::Object(loader.as_obj()),
+            ],
+        )?
+        .l()?;
+
+    env.delete_local_ref(binary_name)?;
+
+    Ok(class.into())
+}
+
+/// A class, looked up by its JNI name (like `java/util/ArrayList`).
+pub struct CachedClass {
+    /// The class's JNI name.
+    name: &'static str,
+
+    /// The class, once it's been looked up.
+    class: RwLock<Option<GlobalRef>>,
+
+    /// Has this been added to [`CACHES`]?
+    registered: Once,
+}
+
+impl CachedClass {
+    /// Create a new [`CachedClass`].
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            class: RwLock::new(None),
+            registered: Once::new(),
+        }
+    }
+
+    /// Get the class's JNI name.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Get the class, looking it up the first time.
+    pub fn get(&'static self, env: &mut JNIEnv) -> Result<GlobalRef> {
+        if let Some(it) = self.class.read().unwrap().as_ref() {
+            return Ok(it.clone());
+        }
+
+        // Not locked while looking it up, since loading the class runs its
+        // static initializer, which could use this cache too.
+        let class = find_class(env, self.name)?;
+        let class = global_ref(env, class.into())?;
+
+        register(&self.registered, self);
+
+        Ok(self.class.write().unwrap().get_or_insert(class).clone())
+    }
+}
+
+impl Clear for CachedClass {
+    fn clear(&self) {
+        self.class.write().unwrap().take();
+    }
+}
+
+/// A kind of member ID, which a [`CachedMember`] can hold.
+pub trait MemberId: Copy + Send + Sync + 'static {
+    /// Look up a member of a class.
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, sig: &str) -> Result<Self>;
+}
+
+impl MemberId for JMethodID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, sig: &str) -> Result<Self> {
+        env.get_method_id(class, name, sig)
+    }
+}
+
+impl MemberId for JStaticMethodID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, sig: &str) -> Result<Self> {
+        env.get_static_method_id(class, name, sig)
+    }
+}
+
+impl MemberId for JFieldID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, sig: &str) -> Result<Self> {
+        env.get_field_id(class, name, sig)
+    }
+}
+
+impl MemberId for JStaticFieldID {
+    fn lookup(env: &mut JNIEnv, class: &JClass, name: &str, sig: &str) -> Result<Self> {
+        env.get_static_field_id(class, name, sig)
+    }
+}
+
+/// The ID of a method or field, looked up by its name and signature. A
+/// cache only belongs to one class, which is passed in when it's looked up.
+pub struct CachedMember<T: MemberId> {
+    /// The member's name (`<init>` for constructors).
+    name: &'static str,
+
+    /// The member's JNI signature, like `(I)V` or `Ljava/lang/String;`.
+    sig: &'static str,
+
+    /// The ID, once it's been looked up.
+    id: RwLock<Option<T>>,
+
+    /// Has this been added to [`CACHES`]?
+    registered: Once,
+}
+
+/// A cached [`JMethodID`], for methods and constructors.
+pub type CachedMethod = CachedMember<JMethodID>;
+
+/// A cached [`JStaticMethodID`].
+pub type CachedStaticMethod = CachedMember<JStaticMethodID>;
+
+/// A cached [`JFieldID`].
+pub type CachedField = CachedMember<JFieldID>;
+
+/// A cached [`JStaticFieldID`].
+pub type CachedStaticField = CachedMember<JStaticFieldID>;
+
+impl<T: MemberId> CachedMember<T> {
+    /// Create a new [`CachedMember`].
+    pub const fn new(name: &'static str, sig: &'static str) -> Self {
+        Self {
+            name,
+            sig,
+            id: RwLock::new(None),
+            registered: Once::new(),
+        }
+    }
+
+    /// Get the ID, looking it up in `class` the first time.
+    pub fn get(&'static self, env: &mut JNIEnv, class: &GlobalRef) -> Result<T> {
+        if let Some(it) = *self.id.read().unwrap() {
+            return Ok(it);
+        }
+
+        let id = T::lookup(env, <&JClass>::from(class.as_obj()), self.name, self.sig)?;
+
+        register(&self.registered, self);
+
+        Ok(*self.id.write().unwrap().get_or_insert(id))
+    }
+}
+
+impl<T: MemberId> Clear for CachedMember<T> {
+    fn clear(&self) {
+        self.id.write().unwrap().take();
+    }
+}
+
+/// Values looked up by name, for when the names aren't known up front.
+pub struct CachedMap<T: Clone + Send + Sync + 'static> {
+    /// The values, by name.
+    map: RwLock<Option<HashMap<String, T>>>,
+
+    /// Has this been added to [`CACHES`]?
+    registered: Once,
+}
+
+impl<T: Clone + Send + Sync + 'static> Default for CachedMap<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> CachedMap<T> {
+    /// Create a new [`CachedMap`].
+    pub const fn new() -> Self {
+        Self {
+            map: RwLock::new(None),
+            registered: Once::new(),
+        }
+    }
+
+    /// Get a value, looking it up with `lookup` the first time. The cache
+    /// isn't locked while looking it up.
+    pub fn get(&'static self, name: &str, lookup: impl FnOnce() -> Result<T>) -> Result<T> {
+        if let Some(it) = self
+            .map
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|it| it.get(name))
+        {
+            return Ok(it.clone());
+        }
+
+        let val = lookup()?;
+
+        register(&self.registered, self);
+
+        Ok(self
+            .map
+            .write()
+            .unwrap()
+            .get_or_insert_default()
+            .entry(name.into())
+            .or_insert(val)
+            .clone())
+    }
+}
+
+impl<T: Clone + Send + Sync + 'static> Clear for CachedMap<T> {
+    fn clear(&self) {
+        self.map.write().unwrap().take();
+    }
+}
@@ -0,0 +1,26 @@
+//! Calling Java from Rust, used by the stubs for `extern class`es.
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{GlobalRef, JObject, JString},
+};
+
+/// Turn a local reference into a [`GlobalRef`], deleting the local one.
+pub fn global_ref(env: &mut JNIEnv, obj: JObject) -> Result<GlobalRef> {
+    let global = env.new_global_ref(&obj)?;
+
+    env.delete_local_ref(obj)?;
+
+    Ok(global)
+}
+
+/// Read a local reference to a Java `String`, deleting it.
+pub fn local_string(env: &mut JNIEnv, obj: JObject) -> Result<String> {
+    let obj = JString::from(obj);
+    let val = env.get_string(&obj)?.into();
+
+    env.delete_local_ref(obj)?;
+
+    Ok(val)
+}
@@ -1,5 +1,11 @@
 //! Internal conversions
 
+use jni::{
+    JNIEnv,
+    errors::{Error, Result},
+    objects::JObject,
+};
+
 /// A trait for objects that convert into Java types.
 pub trait AsJava<'a> {
     /// Get the function associated with getting this type.
@@ -50,3 +56,18 @@ conversion!(f64 => jdouble: getDouble);
 // TODO
 // conversion!(u128 => jbigint);
 // conversion!(i128 => jbigint);
+
+/// Check that an object about to be converted isn't `null`, throwing a
+/// `NullPointerException` naming its class if it is.
+pub fn check_non_null(env: &mut JNIEnv, obj: &JObject, class: &'static str) -> Result<()> {
+    if !obj.is_null() {
+        return Ok(());
+    }
+
+    env.throw_new(
+        "java/lang/NullPointerException",
+        format!("Can't convert a null {class}"),
+    )?;
+
+    Err(Error::NullPtr(class))
+}
@@ -0,0 +1,142 @@
+//! Per-object locks and owner checks, for classes declared `sync`, `send` or
+//! `local`.
+//!
+//! Natives look up their object's [`ObjectGuard`] by its pointer before they
+//! borrow it, so it isn't part of the object itself. `sync` objects are shared
+//! by calls that take `&self` and locked by ones that change them, `send`
+//! objects are locked by every call, and `local` objects can only be used from
+//! the thread that created them.
+//!
+//! Guards are registered when Java wraps a pointer, and marked as freed (under
+//! the write lock) when their object is freed. Natives check that mark once
+//! they hold the lock, so a call waiting on a `free()` throws instead of using
+//! the freed object.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard},
+    thread::{self, ThreadId},
+};
+
+use jni::{JNIEnv, sys::jlong};
+
+/// Every guarded object's guard, by pointer.
+static GUARDS: RwLock<Option<HashMap<jlong, Arc<ObjectGuard>>>> = RwLock::new(None);
+
+/// The lock and owner of an object.
+#[derive(Debug)]
+pub struct ObjectGuard {
+    /// The lock, holding whether the object has been freed. Natives panicking
+    /// abort, so it can't really be poisoned.
+    lock: RwLock<bool>,
+
+    /// The thread that created the object.
+    owner: ThreadId,
+}
+
+impl ObjectGuard {
+    /// Create a new [`ObjectGuard`], owned by the current thread.
+    pub fn new() -> Self {
+        Self {
+            lock: RwLock::new(false),
+            owner: thread::current().id(),
+        }
+    }
+
+    /// Lock the object for a call sharing it with others.
+    pub fn read(&self) -> RwLockReadGuard<'_, bool> {
+        self.lock.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Lock the object for a call that changes (or frees) it.
+    pub fn write(&self) -> RwLockWriteGuard<'_, bool> {
+        self.lock.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Get the thread that created the object.
+    pub fn owner(&self) -> ThreadId {
+        self.owner
+    }
+
+    /// Check that the current thread owns the object, throwing an
+    /// `IllegalStateException` if it doesn't.
+    pub fn check_owner(&self, env: &mut JNIEnv, class: &str) -> bool {
+        if self.owner == thread::current().id() {
+            return true;
+        }
+
+        let _ = env.throw_new(
+            "java/lang/IllegalStateException",
+            format!("{class} can only be used from the thread that created it"),
+        );
+
+        false
+    }
+}
+
+/// Check that an object hasn't been freed, given the flag its lock holds,
+/// throwing an `IllegalStateException` if it has.
+pub fn check_not_freed(env: &mut JNIEnv, freed: bool, class: &str) -> bool {
+    if !freed {
+        return true;
+    }
+
+    throw_freed(env, class);
+
+    false
+}
+
+/// Throw the `IllegalStateException` for using a freed object.
+fn throw_freed(env: &mut JNIEnv, class: &str) {
+    let _ = env.throw_new(
+        "java/lang/IllegalStateException",
+        format!("{class} was already freed"),
+    );
+}
+
+impl Default for ObjectGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Register a guard for the object at `ptr`, owned by the current thread, when
+/// Java wraps it. Wrapping an object that's already registered keeps its guard.
+pub fn register_object_guard(ptr: jlong) {
+    GUARDS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_default()
+        .entry(ptr)
+        .or_default();
+}
+
+/// Get the guard of the object at `ptr`. If it has none, it was already freed,
+/// so this throws an `IllegalStateException` for `class` and returns [`None`].
+pub fn object_guard(env: &mut JNIEnv, ptr: jlong, class: &str) -> Option<Arc<ObjectGuard>> {
+    let guard = GUARDS
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_ref()
+        .and_then(|it| it.get(&ptr))
+        .cloned();
+
+    if guard.is_none() {
+        throw_freed(env, class);
+    }
+
+    guard
+}
+
+/// Drop the guard of the object at `ptr`, once it's been marked as freed.
+/// Anything still holding the guard keeps it until it's done, and sees the
+/// mark once it gets the lock.
+pub fn drop_object_guard(ptr: jlong) {
+    if let Some(guards) = GUARDS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+    {
+        guards.remove(&ptr);
+    }
+}
@@ -1,11 +1,17 @@
 //! Utils for Java bindings
 
 use jni::{
-    objects::JValueGen,
-    sys::{jlong, jobject},
+    objects::{GlobalRef, JClass, JMethodID, JObject, JValue, JValueOwned},
+    signature::{Primitive, ReturnType},
+    sys::{jlong, jobject, jvalue},
     JNIEnv,
 };
 
+use super::{
+    cache::{CachedClass, CachedMap, CachedMethod, CachedStaticMethod, find_class},
+    call::global_ref,
+};
+
 /// Convert a [`jlong`] to a `*mut T`
 #[cfg(target_pointer_width = "32")]
 pub unsafe fn jlong_to_pointer<T>(val: jlong) -> *mut T {
@@ -18,19 +24,172 @@ pub unsafe fn jlong_to_pointer<T>(val: jlong) -> *mut T {
     val as *mut T
 }
 
-/// Convert an object (`T`) to a [`jobject`]
-#[allow(dead_code)]
-pub fn object_to_jobject<T>(mut env: JNIEnv, obj: T, jcls: String) -> jobject {
-    let jobj = env.alloc_object(jcls).unwrap();
+/// The `(long)` constructors of generated classes, by class name.
+static CONSTRUCTORS: CachedMap<(GlobalRef, JMethodID)> = CachedMap::new();
+
+/// Get a generated class and its `(long)` constructor, looking them up the
+/// first time they're used.
+fn constructor(env: &mut JNIEnv, class: &str) -> (GlobalRef, JMethodID) {
+    CONSTRUCTORS
+        .get(class, || {
+            let cls = find_class(env, class)
+                .unwrap_or_else(|_| panic!("pointer_to_jobject: Can not find class {class}"));
+
+            let ctor = env
+                .get_method_id(&cls, "<init>", "(J)V")
+                .unwrap_or_else(|_| {
+                    panic!("pointer_to_jobject: {class} has no (long) constructor")
+                });
+
+            Ok((global_ref(env, cls.into())?, ctor))
+        })
+        .unwrap()
+}
+
+/// Wrap a pointer to a wrapper struct in an instance of its generated class
+/// (like `com/example/MyStruct`), which takes ownership of it.
+///
+/// A null pointer becomes `null`.
+pub fn pointer_to_jobject(env: &mut JNIEnv, class: &str, ptr: jlong) -> jobject {
+    if ptr == 0 {
+        return JObject::null().as_raw();
+    }
+
+    let (cls, ctor) = constructor(env, class);
+
+    new_instance(env, cls.as_obj().into(), ctor, ptr)
+}
+
+/// Wrap a pointer in an instance of `class`, which has to have a `(long)`
+/// constructor, like generated classes. Unlike [`pointer_to_jobject`], the
+/// constructor isn't cached.
+///
+/// A null pointer becomes `null`. So does a class without the constructor,
+/// leaving a `NoSuchMethodError` to be thrown in Java.
+pub fn pointer_to_instance(env: &mut JNIEnv, class: &JClass, ptr: jlong) -> jobject {
+    if ptr == 0 {
+        return JObject::null().as_raw();
+    }
+
+    let Ok(ctor) = env.get_method_id(class, "<init>", "(J)V") else {
+        return JObject::null().as_raw();
+    };
+
+    new_instance(env, class, ctor, ptr)
+}
+
+/// Call a `(long)` constructor.
+fn new_instance(env: &mut JNIEnv, class: &JClass, ctor: JMethodID, ptr: jlong) -> jobject {
+    unsafe { env.new_object_unchecked(class, ctor, &[jvalue { j: ptr }]) }
+        .expect("pointer_to_jobject: Can not create the object")
+        .as_raw()
+}
+
+/// Convert an object (`T`) to a [`jobject`] of a generated class (like
+/// `com/example/MyStruct`). `T` has to be its wrapper struct (`__JNI_MyStruct`),
+/// which has the class name as `JAVA_CLASS`.
+pub fn object_to_jobject<T>(env: &mut JNIEnv, obj: T, class: &str) -> jobject {
+    let ptr = Box::into_raw(Box::new(obj)) as jlong;
 
-    assert!(!jobj.is_null(), "object_to_jobject: AllocObject failed");
+    pointer_to_jobject(env, class, ptr)
+}
+
+/// Convert a [`JValue`] to a [`jobject`], boxing primitives (`int` becomes `Integer`, etc.).
+///
+/// `void` becomes `null`.
+pub fn jvalue_to_jobject(env: &mut JNIEnv, val: JValue) -> jobject {
+    static BOXES: [(CachedClass, CachedStaticMethod); 8] = [
+        box_class("java/lang/Boolean", "(Z)Ljava/lang/Boolean;"),
+        box_class("java/lang/Byte", "(B)Ljava/lang/Byte;"),
+        box_class("java/lang/Character", "(C)Ljava/lang/Character;"),
+        box_class("java/lang/Short", "(S)Ljava/lang/Short;"),
+        box_class("java/lang/Integer", "(I)Ljava/lang/Integer;"),
+        box_class("java/lang/Long", "(J)Ljava/lang/Long;"),
+        box_class("java/lang/Float", "(F)Ljava/lang/Float;"),
+        box_class("java/lang/Double", "(D)Ljava/lang/Double;"),
+    ];
 
-    let ret = Box::into_raw(Box::new(obj)) as jlong;
-    let res = env.set_field(&jobj, "__pointer", "Long", JValueGen::Long(ret));
+    let (class, value_of) = match val {
+        JValue::Object(obj) => return obj.as_raw(),
+        JValue::Void => return std::ptr::null_mut(),
+        JValue::Bool(_) => &BOXES[0],
+        JValue::Byte(_) => &BOXES[1],
+        JValue::Char(_) => &BOXES[2],
+        JValue::Short(_) => &BOXES[3],
+        JValue::Int(_) => &BOXES[4],
+        JValue::Long(_) => &BOXES[5],
+        JValue::Float(_) => &BOXES[6],
+        JValue::Double(_) => &BOXES[7],
+    };
 
-    if env.exception_check().unwrap() || res.is_err() {
-        panic!("object_to_jobject: Can not set mNativeObj field: catch exception");
+    let class = class.get(env).unwrap();
+    let value_of = value_of.get(env, &class).unwrap();
+
+    unsafe {
+        env.call_static_method_unchecked(
+            <&JClass>::from(class.as_obj()),
+            value_of,
+            ReturnType::Object,
+            &[val.as_jni()],
+        )
     }
+    .unwrap()
+    .l()
+    .unwrap()
+    .as_raw()
+}
+
+/// A boxed primitive class, and its `valueOf` method.
+const fn box_class(class: &'static str, sig: &'static str) -> (CachedClass, CachedStaticMethod) {
+    (
+        CachedClass::new(class),
+        CachedStaticMethod::new("valueOf", sig),
+    )
+}
+
+/// Convert a boxed primitive to a [`JValueOwned`] (`Integer` becomes `int`,
+/// etc.), the opposite of [`jvalue_to_jobject`].
+///
+/// `void` becomes [`JValueOwned::Void`].
+pub fn jobject_to_jvalue<'a>(
+    env: &mut JNIEnv<'a>,
+    obj: &JObject,
+    ty: Primitive,
+) -> JValueOwned<'a> {
+    static UNBOXES: [(CachedClass, CachedMethod); 8] = [
+        unbox_class("java/lang/Boolean", "booleanValue", "()Z"),
+        unbox_class("java/lang/Byte", "byteValue", "()B"),
+        unbox_class("java/lang/Character", "charValue", "()C"),
+        unbox_class("java/lang/Short", "shortValue", "()S"),
+        unbox_class("java/lang/Integer", "intValue", "()I"),
+        unbox_class("java/lang/Long", "longValue", "()J"),
+        unbox_class("java/lang/Float", "floatValue", "()F"),
+        unbox_class("java/lang/Double", "doubleValue", "()D"),
+    ];
+
+    let (class, value) = match ty {
+        Primitive::Void => return JValueOwned::Void,
+        Primitive::Boolean => &UNBOXES[0],
+        Primitive::Byte => &UNBOXES[1],
+        Primitive::Char => &UNBOXES[2],
+        Primitive::Short => &UNBOXES[3],
+        Primitive::Int => &UNBOXES[4],
+        Primitive::Long => &UNBOXES[5],
+        Primitive::Float => &UNBOXES[6],
+        Primitive::Double => &UNBOXES[7],
+    };
+
+    let class = class.get(env).unwrap();
+    let value = value.get(env, &class).unwrap();
+
+    unsafe { env.call_method_unchecked(obj, value, ReturnType::Primitive(ty), &[]) }.unwrap()
+}
 
-    jobj.as_raw()
+/// A boxed primitive class, and its unboxing method.
+const fn unbox_class(
+    class: &'static str,
+    name: &'static str,
+    sig: &'static str,
+) -> (CachedClass, CachedMethod) {
+    (CachedClass::new(class), CachedMethod::new(name, sig))
 }
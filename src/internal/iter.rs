@@ -0,0 +1,90 @@
+//! Support for methods returning Rust iterators.
+//!
+//! Iterators are boxed and handed to Java as a pointer, which is wrapped in a
+//! `NativeIterator`. Every call to `next()` pulls one item across and
+//! converts it to a `jobject`. The iterator is dropped as soon as it's
+//! exhausted, or when the `NativeIterator` is closed.
+
+use jni::{
+    JNIEnv,
+    objects::{JClass, JObject},
+    sys::{jlong, jobject},
+};
+
+type NextFn = Box<dyn for<'a> FnMut(&mut JNIEnv<'a>) -> Option<jobject>>;
+
+/// A type-erased iterator, converting its items to Java objects.
+pub struct JavaIterator {
+    next: NextFn,
+}
+
+impl JavaIterator {
+    /// Create a new [`JavaIterator`] from an iterator and a conversion
+    /// function for its items.
+    pub fn new<I, C>(mut iter: I, mut convert: C) -> Self
+    where
+        I: Iterator + 'static,
+        C: for<'a> FnMut(&mut JNIEnv<'a>, I::Item) -> jobject + 'static,
+    {
+        Self {
+            next: Box::new(move |env| iter.next().map(|val| convert(env, val))),
+        }
+    }
+
+    /// Get the next item, converted to a Java object.
+    pub fn next(&mut self, env: &mut JNIEnv) -> Option<jobject> {
+        (self.next)(env)
+    }
+}
+
+/// Box an iterator for a `NativeIterator`, returning its pointer.
+pub fn iterator_to_jlong<I, C>(iter: I, convert: C) -> jlong
+where
+    I: Iterator + 'static,
+    C: for<'a> FnMut(&mut JNIEnv<'a>, I::Item) -> jobject + 'static,
+{
+    Box::leak(Box::new(JavaIterator::new(iter, convert))) as *mut JavaIterator as jlong
+}
+
+/// Get the next item of the iterator, or `done` if it's exhausted.
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeIterator_nextJni<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+    done: JObject<'local>,
+) -> jobject {
+    let iter = unsafe { &mut *(ptr as *mut JavaIterator) };
+
+    iter.next(&mut env).unwrap_or(done.as_raw())
+}
+
+/// Drop the iterator.
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeIterator_freeJni<'local>(
+    env: JNIEnv<'local>,
+    class: JClass<'local>,
+    ptr: jlong,
+) {
+    drop(unsafe { Box::from_raw(ptr as *mut JavaIterator) });
+}
@@ -0,0 +1,241 @@
+//! Tracking the objects made for Java, when bindings are made with
+//! [`BindgenConfig::track_leaks`](crate::build::BindgenConfig::track_leaks).
+//!
+//! Every object boxed for Java is registered here with its class, and
+//! unregistered when it's freed, so whatever is still registered is either in
+//! use or leaked. With [`set_leak_traces`], the Java stack trace of each
+//! allocation is kept too. These can be read from Java through the generated
+//! `NativeLeaks` class, which can print a report when the JVM shuts down.
+
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, HashMap},
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{JClass, JObject, JObjectArray, JString},
+    signature::ReturnType,
+    sys::{jboolean, jlong, jstring},
+};
+
+use super::{
+    cache::{CachedClass, CachedMethod},
+    vm::{current_env, frame_capacity},
+};
+
+/// Every live object, by address.
+static LIVE: Mutex<Option<HashMap<usize, LiveObject>>> = Mutex::new(None);
+
+/// Are Java stack traces kept?
+static TRACES: AtomicBool = AtomicBool::new(false);
+
+static THROWABLE: CachedClass = CachedClass::new("java/lang/Throwable");
+static THROWABLE_NEW: CachedMethod = CachedMethod::new("<init>", "()V");
+static THROWABLE_TRACE: CachedMethod =
+    CachedMethod::new("getStackTrace", "()[Ljava/lang/StackTraceElement;");
+
+static OBJECT: CachedClass = CachedClass::new("java/lang/Object");
+static OBJECT_TO_STRING: CachedMethod = CachedMethod::new("toString", "()Ljava/lang/String;");
+
+/// An object that's been made for Java and not freed.
+struct LiveObject {
+    /// The class, like `com.example.MyStruct`.
+    class: &'static str,
+
+    /// Where it was made, if traces are kept.
+    trace: Option<String>,
+}
+
+/// The objects of a class that are still alive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LiveObjects {
+    /// The class, like `com.example.MyStruct`.
+    pub class: &'static str,
+
+    /// The number of objects.
+    pub count: usize,
+
+    /// The Java stack traces of the objects that have them, one frame per line.
+    pub traces: Vec<String>,
+}
+
+/// Enable/disable keeping the Java stack trace of every object made from now
+/// on. Off by default, since getting a trace is slow.
+pub fn set_leak_traces(enable: bool) {
+    TRACES.store(enable, Ordering::Relaxed);
+}
+
+/// Register an object made for Java, returning its pointer.
+pub fn track_alloc<T>(ptr: *mut T, class: &'static str) -> *mut T {
+    let trace = maybe_trace();
+
+    LIVE.lock()
+        .unwrap()
+        .get_or_insert_default()
+        .insert(ptr as usize, LiveObject { class, trace });
+
+    ptr
+}
+
+/// Unregister an object that's about to be freed. Does nothing if it wasn't
+/// registered.
+pub fn track_free<T>(ptr: *const T) {
+    if let Some(live) = LIVE.lock().unwrap().as_mut() {
+        live.remove(&(ptr as usize));
+    }
+}
+
+/// Get the current Java stack trace, if traces are kept.
+fn maybe_trace() -> Option<String> {
+    if !TRACES.load(Ordering::Relaxed) {
+        return None;
+    }
+
+    current_env()
+        .and_then(|mut env| env.with_local_frame(frame_capacity(), java_trace))
+        .ok()
+}
+
+/// Get the current Java stack trace, one frame per line.
+fn java_trace(env: &mut JNIEnv) -> Result<String> {
+    let class = THROWABLE.get(env)?;
+    let new = THROWABLE_NEW.get(env, &class)?;
+    let get_trace = THROWABLE_TRACE.get(env, &class)?;
+    let object = OBJECT.get(env)?;
+    let to_string = OBJECT_TO_STRING.get(env, &object)?;
+
+    let err = unsafe { env.new_object_unchecked(<&JClass>::from(class.as_obj()), new, &[]) }?;
+    let frames = unsafe { env.call_method_unchecked(&err, get_trace, ReturnType::Object, &[]) }?;
+    let frames = JObjectArray::from(frames.l()?);
+    let mut lines = Vec::new();
+
+    for i in 0..env.get_array_length(&frames)? {
+        let frame = env.get_object_array_element(&frames, i)?;
+        let line =
+            unsafe { env.call_method_unchecked(&frame, to_string, ReturnType::Object, &[]) }?;
+        let line = JString::from(line.l()?);
+
+        lines.push(format!("at {}", env.get_string(&line)?.to_string_lossy()));
+
+        env.delete_local_ref(line)?;
+        env.delete_local_ref(frame)?;
+    }
+
+    Ok(lines.join("\n"))
+}
+
+/// Get the number of objects made for Java that are still alive.
+pub fn live_count() -> usize {
+    LIVE.lock().unwrap().as_ref().map_or(0, |it| it.len())
+}
+
+/// Get the objects made for Java that are still alive, by class, the classes
+/// with the most objects first.
+pub fn live_objects() -> Vec<LiveObjects> {
+    let mut classes = BTreeMap::<&'static str, LiveObjects>::new();
+
+    for it in LIVE.lock().unwrap().iter().flat_map(|it| it.values()) {
+        let entry = classes.entry(it.class).or_insert_with(|| LiveObjects {
+            class: it.class,
+            count: 0,
+            traces: Vec::new(),
+        });
+
+        entry.count += 1;
+        entry.traces.extend(it.trace.clone());
+    }
+
+    let mut classes = classes.into_values().collect::<Vec<_>>();
+
+    classes.sort_by_key(|it| Reverse(it.count));
+    classes
+}
+
+/// Get a report of the objects still alive, one class per line, followed by
+/// their stack traces (if they were kept).
+pub fn leak_report() -> String {
+    let live = live_objects();
+
+    if live.is_empty() {
+        return "No live native objects\n".into();
+    }
+
+    let mut out = format!("{} live native objects:\n", live_count());
+
+    for it in live {
+        out.push_str(&format!("  {}: {}\n", it.class, it.count));
+
+        for trace in it.traces {
+            out.push_str("    Allocated:\n");
+
+            for line in trace.lines() {
+                out.push_str(&format!("      {line}\n"));
+            }
+        }
+    }
+
+    out
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLeaks_reportJni<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) -> jstring {
+    env.new_string(leak_report())
+        .map(|it| it.into_raw())
+        .unwrap_or(JObject::null().into_raw())
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLeaks_liveCount<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) -> jlong {
+    live_count() as jlong
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLeaks_keepTraces<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    enable: jboolean,
+) {
+    set_leak_traces(enable != 0);
+}
@@ -0,0 +1,397 @@
+//! Forwarding `log` records to Java, through SLF4J if it's on the classpath,
+//! or `java.util.logging` if it isn't. Needs the `log` feature.
+//!
+//! Each record goes to the Java logger named after its target, with `::`
+//! replaced by `.` (so `my_crate::net` logs to `my_crate.net`), unless a
+//! different mapping is set with [`JavaLogger::logger_name`].
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+};
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{GlobalRef, JClass, JObject, JString, JValue},
+    signature::{Primitive, ReturnType},
+    sys::{jboolean, jstring},
+};
+use log::{Level, LevelFilter, Log, Metadata, Record, SetLoggerError};
+
+use super::{
+    cache::{CachedClass, CachedMap, CachedMethod, find_class},
+    call::global_ref,
+    vm::attach_scoped,
+};
+
+thread_local! {
+    /// Is this thread already logging? Anything logged while forwarding a
+    /// record is dropped, instead of recursing.
+    static LOGGING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// The Java logging API records are sent to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum JavaBackend {
+    /// SLF4J's `org.slf4j.Logger`.
+    Slf4j,
+
+    /// `java.util.logging.Logger`.
+    Jul,
+}
+
+/// A [`Log`] implementation sending records to Java.
+pub struct JavaLogger {
+    /// The max level to set when installing.
+    max_level: LevelFilter,
+
+    /// Turns a record's target into a Java logger name.
+    name: fn(&str) -> String,
+
+    /// The backend, found with the first record.
+    backend: OnceLock<JavaBackend>,
+
+    /// Java loggers, by target.
+    loggers: Mutex<HashMap<String, GlobalRef>>,
+}
+
+impl Default for JavaLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JavaLogger {
+    /// Create a new [`JavaLogger`], logging `info` and up.
+    pub fn new() -> Self {
+        Self {
+            max_level: LevelFilter::Info,
+            name: default_logger_name,
+            backend: OnceLock::new(),
+            loggers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the max level to log. This can be changed later with
+    /// [`log::set_max_level`], or `NativeLogging.setMaxLevel` from Java.
+    pub fn max_level(mut self, val: LevelFilter) -> Self {
+        self.max_level = val;
+        self
+    }
+
+    /// Set how a record's target is turned into a Java logger name.
+    pub fn logger_name(mut self, val: fn(&str) -> String) -> Self {
+        self.name = val;
+        self
+    }
+
+    /// Use a backend, instead of looking for SLF4J.
+    pub fn backend(self, val: JavaBackend) -> Self {
+        let _ = self.backend.set(val);
+        self
+    }
+
+    /// Install this as the logger. Fails if there already is one.
+    pub fn install(self) -> std::result::Result<(), SetLoggerError> {
+        let level = self.max_level;
+
+        log::set_boxed_logger(Box::new(self))?;
+        log::set_max_level(level);
+
+        Ok(())
+    }
+
+    /// Send a record to Java. A pending exception would make every call
+    /// fail, so it's put back afterwards, and ones thrown while logging are
+    /// dropped.
+    fn forward(&self, env: &mut JNIEnv, record: &Record) -> Result<()> {
+        let pending = env.exception_occurred()?;
+
+        if !pending.is_null() {
+            env.exception_clear()?;
+        }
+
+        let res = env.with_local_frame(8, |env| self.send(env, record));
+
+        if env.exception_check()? {
+            env.exception_clear()?;
+        }
+
+        if !pending.is_null() {
+            env.throw(&pending)?;
+        }
+
+        env.delete_local_ref(pending)?;
+
+        res
+    }
+
+    fn send(&self, env: &mut JNIEnv, record: &Record) -> Result<()> {
+        static SLF4J_CLASS: CachedClass = CachedClass::new("org/slf4j/Logger");
+        static JUL_CLASS: CachedClass = CachedClass::new("java/util/logging/Logger");
+
+        static SLF4J_METHODS: [CachedMethod; 5] = [
+            CachedMethod::new("error", "(Ljava/lang/String;)V"),
+            CachedMethod::new("warn", "(Ljava/lang/String;)V"),
+            CachedMethod::new("info", "(Ljava/lang/String;)V"),
+            CachedMethod::new("debug", "(Ljava/lang/String;)V"),
+            CachedMethod::new("trace", "(Ljava/lang/String;)V"),
+        ];
+
+        static JUL_METHOD: CachedMethod =
+            CachedMethod::new("log", "(Ljava/util/logging/Level;Ljava/lang/String;)V");
+
+        let backend = *self.backend.get_or_init(|| detect_backend(env));
+        let logger = self.java_logger(env, backend, record.target())?;
+        let msg = env.new_string(record.args().to_string())?;
+
+        let (id, args) = match backend {
+            JavaBackend::Slf4j => {
+                let class = SLF4J_CLASS.get(env)?;
+                let id = SLF4J_METHODS[record.level() as usize - 1].get(env, &class)?;
+
+                (id, vec![JValue::Object(&msg).as_jni()])
+            }
+
+            JavaBackend::Jul => {
+                let class = JUL_CLASS.get(env)?;
+                let id = JUL_METHOD.get(env, &class)?;
+                let level = jul_level(env, record.level())?;
+
+                (
+                    id,
+                    vec![
+                        JValue::Object(level.as_obj()).as_jni(),
+                        JValue::Object(&msg).as_jni(),
+                    ],
+                )
+            }
+        };
+
+        let ret = ReturnType::Primitive(Primitive::Void);
+
+        unsafe { env.call_method_unchecked(logger.as_obj(), id, ret, &args) }?;
+
+        Ok(())
+    }
+
+    /// Get the Java logger for a target, creating it the first time.
+    fn java_logger(
+        &self,
+        env: &mut JNIEnv,
+        backend: JavaBackend,
+        target: &str,
+    ) -> Result<GlobalRef> {
+        if let Some(it) = self.loggers.lock().unwrap().get(target) {
+            return Ok(it.clone());
+        }
+
+        let name = env.new_string((self.name)(target))?;
+
+        let logger = match backend {
+            JavaBackend::Slf4j => env
+                .call_static_method(
+                    "org/slf4j/LoggerFactory",
+                    "getLogger",
+                    "(Ljava/lang/String;)Lorg/slf4j/Logger;",
+                    &[JValue::Object(&name)],
+                )?
+                .l()?,
+
+            JavaBackend::Jul => jul_logger(env, &name)?,
+        };
+
+        let logger = global_ref(env, logger)?;
+
+        self.loggers
+            .lock()
+            .unwrap()
+            .insert(target.into(), logger.clone());
+
+        Ok(logger)
+    }
+}
+
+impl Log for JavaLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        // The `jni` crate logs while threads detach, when they can't be
+        // attached again.
+        let jni = record.target() == "jni" || record.target().starts_with("jni::");
+
+        if !self.enabled(record.metadata()) || jni || LOGGING.get() {
+            return;
+        }
+
+        LOGGING.set(true);
+
+        // Records from before Java loaded the library have nowhere to go.
+        // Threads that aren't attached (like ones Rust started) are only
+        // attached while the record is sent, instead of for good.
+        if let Ok(mut env) = attach_scoped() {
+            let _ = self.forward(&mut env, record);
+        }
+
+        LOGGING.set(false);
+    }
+
+    fn flush(&self) {}
+}
+
+/// The default logger name for a target, with `::` replaced by `.`.
+pub fn default_logger_name(target: &str) -> String {
+    target.replace("::", ".")
+}
+
+/// Get a `java.util.logging.Logger`. `Logger.getLogger` looks at its caller,
+/// and fails on threads that aren't running any Java code, so this goes
+/// through the `LogManager`, creating the logger if it isn't there.
+fn jul_logger<'a>(env: &mut JNIEnv<'a>, name: &JString) -> Result<JObject<'a>> {
+    let sig = "(Ljava/lang/String;)Ljava/util/logging/Logger;";
+    let args = [JValue::Object(name)];
+
+    let manager = env
+        .call_static_method(
+            "java/util/logging/LogManager",
+            "getLogManager",
+            "()Ljava/util/logging/LogManager;",
+            &[],
+        )?
+        .l()?;
+
+    let logger = env.call_method(&manager, "getLogger", sig, &args)?.l()?;
+
+    if !logger.is_null() {
+        return Ok(logger);
+    }
+
+    let logger = env.new_object(
+        "java/util/logging/Logger",
+        "(Ljava/lang/String;Ljava/lang/String;)V",
+        &[JValue::Object(name), JValue::Object(&JObject::null())],
+    )?;
+
+    let logger = JValue::Object(&logger);
+
+    env.call_method(
+        &manager,
+        "addLogger",
+        "(Ljava/util/logging/Logger;)Z",
+        &[logger],
+    )?;
+
+    // Another thread could have added one first.
+    env.call_method(&manager, "getLogger", sig, &args)?.l()
+}
+
+/// Use SLF4J if it's there, and `java.util.logging` if it isn't.
+fn detect_backend(env: &mut JNIEnv) -> JavaBackend {
+    match find_class(env, "org/slf4j/LoggerFactory") {
+        Ok(it) => {
+            let _ = env.delete_local_ref(it);
+
+            JavaBackend::Slf4j
+        }
+
+        Err(_) => {
+            let _ = env.exception_clear();
+
+            JavaBackend::Jul
+        }
+    }
+}
+
+/// Get the `java.util.logging.Level` for a level.
+fn jul_level(env: &mut JNIEnv, level: Level) -> Result<GlobalRef> {
+    static LEVELS: CachedMap<GlobalRef> = CachedMap::new();
+
+    let name = match level {
+        Level::Error => "SEVERE",
+        Level::Warn => "WARNING",
+        Level::Info => "INFO",
+        Level::Debug => "FINE",
+        Level::Trace => "FINEST",
+    };
+
+    LEVELS.get(name, || {
+        let val = env
+            .get_static_field("java/util/logging/Level", name, "Ljava/util/logging/Level;")?
+            .l()?;
+
+        global_ref(env, val)
+    })
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLogging_install<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) -> jboolean {
+    JavaLogger::new().install().is_ok() as jboolean
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLogging_setMaxLevel<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+    level: JString<'local>,
+) {
+    let level = env.get_string(&level).map(String::from).unwrap_or_default();
+
+    match LevelFilter::from_str(&level) {
+        Ok(it) => log::set_max_level(it),
+
+        Err(_) => {
+            let _ = env.throw_new(
+                "java/lang/IllegalArgumentException",
+                format!("Unknown log level: {level}"),
+            );
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeLogging_getMaxLevel<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) -> jstring {
+    env.new_string(log::max_level().as_str())
+        .map(|it| it.into_raw())
+        .unwrap_or(JObject::null().into_raw())
+}
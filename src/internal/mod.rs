@@ -1,6 +1,21 @@
 //! Internal functions & utilities
 
+pub mod array;
 pub mod base;
+pub mod cache;
+pub mod call;
 pub mod conv;
+pub mod guard;
 pub mod include;
+pub mod iter;
+
+#[cfg(feature = "log")]
+pub mod logging;
+
+pub mod leaks;
+pub mod path;
+pub mod stats;
+pub mod task;
+pub mod time;
 pub mod types;
+pub mod vm;
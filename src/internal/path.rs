@@ -0,0 +1,69 @@
+//! Conversions between `std::path` types and `java.nio.file.Path` objects.
+//!
+//! Converting a `null` path throws a `java.lang.NullPointerException`. When a
+//! conversion throws, it returns an [`Err`], leaving the exception pending.
+
+use std::path::{Path, PathBuf};
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{JClass, JObject, JString, JValue},
+    signature::ReturnType,
+    sys::jobject,
+};
+
+use super::{
+    cache::{CachedClass, CachedMethod},
+    conv::check_non_null,
+};
+
+static FILE: CachedClass = CachedClass::new("java/io/File");
+static FILE_NEW: CachedMethod = CachedMethod::new("<init>", "(Ljava/lang/String;)V");
+static FILE_TO_PATH: CachedMethod = CachedMethod::new("toPath", "()Ljava/nio/file/Path;");
+
+static OBJECT: CachedClass = CachedClass::new("java/lang/Object");
+static OBJECT_TO_STRING: CachedMethod = CachedMethod::new("toString", "()Ljava/lang/String;");
+
+/// Convert a [`Path`] to a `java.nio.file.Path`.
+///
+/// Java paths are always valid Unicode, so any non-UTF-8 parts of the
+/// path are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+pub fn path_to_jobject(env: &mut JNIEnv, val: impl AsRef<Path>) -> Result<jobject> {
+    let class = FILE.get(env)?;
+    let new = FILE_NEW.get(env, &class)?;
+    let to_path = FILE_TO_PATH.get(env, &class)?;
+    let path = env.new_string(val.as_ref().to_string_lossy())?;
+
+    let file = unsafe {
+        env.new_object_unchecked(
+            <&JClass>::from(class.as_obj()),
+            new,
+            &[JValue::Object(&path).as_jni()],
+        )
+    }?;
+
+    let val = unsafe { env.call_method_unchecked(&file, to_path, ReturnType::Object, &[]) }?.l()?;
+
+    env.delete_local_ref(path)?;
+    env.delete_local_ref(file)?;
+
+    Ok(val.into_raw())
+}
+
+/// Convert a `java.nio.file.Path` to a [`PathBuf`].
+pub fn path_from_jobject(env: &mut JNIEnv, obj: &JObject) -> Result<PathBuf> {
+    check_non_null(env, obj, "java.nio.file.Path")?;
+
+    let class = OBJECT.get(env)?;
+    let to_string = OBJECT_TO_STRING.get(env, &class)?;
+
+    let path =
+        unsafe { env.call_method_unchecked(obj, to_string, ReturnType::Object, &[]) }?.l()?;
+    let path = JString::from(path);
+    let val: String = env.get_string(&path)?.into();
+
+    env.delete_local_ref(path)?;
+
+    Ok(PathBuf::from(val))
+}
@@ -0,0 +1,226 @@
+//! Call counts and timings for generated natives, when bindings are made with
+//! [`BindgenConfig::instrument`](crate::build::BindgenConfig::instrument).
+//!
+//! Every instrumented native has its own [`NativeStat`], which is added to the
+//! list returned by [`native_stats`] the first time it's called. These can be
+//! read from Java through the generated `NativeStats` class too.
+
+use std::{
+    cmp::Reverse,
+    sync::{
+        Mutex, Once,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use jni::{
+    JNIEnv,
+    errors::Result,
+    objects::{JClass, JObject},
+    sys::jobjectArray,
+};
+
+/// Every [`NativeStat`] that's been called.
+static STATS: Mutex<Vec<&'static NativeStat>> = Mutex::new(Vec::new());
+
+/// The counters for one native.
+pub struct NativeStat {
+    /// The Java class, like `com.example.MyStruct`.
+    class: &'static str,
+
+    /// The method.
+    method: &'static str,
+
+    /// The number of calls.
+    calls: AtomicU64,
+
+    /// The time spent in calls, in nanoseconds.
+    nanos: AtomicU64,
+
+    /// Has this been added to [`STATS`]?
+    registered: Once,
+}
+
+impl NativeStat {
+    /// Create a new [`NativeStat`].
+    pub const fn new(class: &'static str, method: &'static str) -> Self {
+        Self {
+            class,
+            method,
+            calls: AtomicU64::new(0),
+            nanos: AtomicU64::new(0),
+            registered: Once::new(),
+        }
+    }
+
+    /// Start timing a call, which is recorded when the timer is dropped.
+    pub fn start(&'static self) -> NativeTimer {
+        NativeTimer {
+            stat: self,
+            start: Instant::now(),
+        }
+    }
+
+    /// Record a call that took some time.
+    pub fn record(&'static self, elapsed: Duration) {
+        self.registered
+            .call_once(|| STATS.lock().unwrap().push(self));
+
+        self.calls.fetch_add(1, Ordering::Relaxed);
+        self.nanos
+            .fetch_add(elapsed.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    /// Get the current counts.
+    pub fn snapshot(&self) -> MethodStats {
+        MethodStats {
+            class: self.class,
+            method: self.method,
+            calls: self.calls.load(Ordering::Relaxed),
+            total: Duration::from_nanos(self.nanos.load(Ordering::Relaxed)),
+        }
+    }
+
+    /// Set the counts back to zero.
+    pub fn reset(&self) {
+        self.calls.store(0, Ordering::Relaxed);
+        self.nanos.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Times a call to a native, until it's dropped.
+pub struct NativeTimer {
+    /// The native being timed.
+    stat: &'static NativeStat,
+
+    /// When the call started.
+    start: Instant,
+}
+
+impl Drop for NativeTimer {
+    fn drop(&mut self) {
+        self.stat.record(self.start.elapsed());
+    }
+}
+
+/// The counts for a native, at some point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MethodStats {
+    /// The Java class, like `com.example.MyStruct`.
+    pub class: &'static str,
+
+    /// The method.
+    pub method: &'static str,
+
+    /// The number of calls.
+    pub calls: u64,
+
+    /// The time spent in calls.
+    pub total: Duration,
+}
+
+impl MethodStats {
+    /// The average time spent in a call.
+    pub fn average(&self) -> Duration {
+        if self.calls == 0 {
+            Duration::ZERO
+        } else {
+            Duration::from_nanos((self.total.as_nanos() / self.calls as u128) as u64)
+        }
+    }
+}
+
+/// Get the counts for every native that's been called, the ones with the most
+/// time spent in them first.
+pub fn native_stats() -> Vec<MethodStats> {
+    let mut stats = STATS
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|it| it.snapshot())
+        .collect::<Vec<_>>();
+
+    stats.sort_by_key(|it| Reverse(it.total));
+    stats
+}
+
+/// Set the counts for every native back to zero.
+pub fn reset_native_stats() {
+    for stat in STATS.lock().unwrap().iter() {
+        stat.reset();
+    }
+}
+
+/// Build the `Object[] { String[] names, long[] calls, long[] nanos }` that
+/// `NativeStats` reads.
+fn stats_to_jobject<'a>(env: &mut JNIEnv<'a>) -> Result<JObject<'a>> {
+    let stats = native_stats();
+    let len = stats.len() as i32;
+
+    let names = env.new_object_array(len, "java/lang/String", JObject::null())?;
+    let calls = env.new_long_array(len)?;
+    let nanos = env.new_long_array(len)?;
+
+    for (i, stat) in stats.iter().enumerate() {
+        let name = env.new_string(format!("{}.{}", stat.class, stat.method))?;
+
+        env.set_object_array_element(&names, i as i32, &name)?;
+        env.delete_local_ref(name)?;
+    }
+
+    let counts = stats.iter().map(|it| it.calls as i64).collect::<Vec<_>>();
+    let times = stats
+        .iter()
+        .map(|it| it.total.as_nanos() as i64)
+        .collect::<Vec<_>>();
+
+    env.set_long_array_region(&calls, 0, &counts)?;
+    env.set_long_array_region(&nanos, 0, &times)?;
+
+    let out = env.new_object_array(3, "java/lang/Object", JObject::null())?;
+
+    env.set_object_array_element(&out, 0, names)?;
+    env.set_object_array_element(&out, 1, calls)?;
+    env.set_object_array_element(&out, 2, nanos)?;
+
+    Ok(out.into())
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeStats_snapshotJni<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) -> jobjectArray {
+    stats_to_jobject(&mut env)
+        .map(|it| it.into_raw())
+        .unwrap_or(JObject::null().into_raw())
+}
+
+#[unsafe(no_mangle)]
+#[allow(
+    unused_mut,
+    unused_variables,
+    unused_unsafe,
+    non_snake_case,
+    improper_ctypes_definitions,
+    no_mangle_generic_items,
+    deprecated,
+    missing_docs
+)]
+pub extern "system" fn Java_org_stardustmodding_rs4j_util_NativeStats_reset<'local>(
+    mut env: JNIEnv<'local>,
+    class: JClass<'local>,
+) {
+    reset_native_stats();
+}
@@ -0,0 +1,282 @@
+//! Support for `async` methods.
+//!
+//! Every `async` method spawns its future on the library's [`TaskExecutor`] and
+//! completes a `java.util.concurrent.CompletableFuture` from the executor's
+//! thread once it's done. Cancelling that future from Java drops the Rust
+//! future the next time it would be polled.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    panic::{AssertUnwindSafe, catch_unwind},
+    pin::Pin,
+    sync::{
+        Arc, Mutex, OnceLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    task::{Context, Poll, Wake, Waker},
+    thread::{self, Thread},
+};
+
+use jni::{
+    JNIEnv, JavaVM,
+    errors::Result,
+    objects::{GlobalRef, JClass, JObject, JValue},
+    signature::{Primitive, ReturnType},
+    sys::{jlong, jobject},
+};
+
+use super::{
+    cache::{CachedClass, CachedMethod},
+    vm::frame_capacity,
+};
+
+static FUTURE: CachedClass = CachedClass::new("java/util/concurrent/CompletableFuture");
+static FUTURE_COMPLETE: CachedMethod = CachedMethod::new("complete", "(Ljava/lang/Object;)Z");
+static FUTURE_COMPLETE_EXCEPTIONALLY: CachedMethod =
+    CachedMethod::new("completeExceptionally", "(Ljava/lang/Throwable;)Z");
+
+static RUNTIME_EXCEPTION: CachedClass = CachedClass::new("java/lang/RuntimeException");
+static RUNTIME_EXCEPTION_NEW: CachedMethod = CachedMethod::new("<init>", "(Ljava/lang/String;)V");
+
+/// A boxed future, as handed to a [`TaskExecutor`].
+pub type Task = Pin<Box<dyn Future<Output = ()> + Send + 'static>>;
+
+/// Something that can drive futures to completion.
+///
+/// Any `Fn(Task)` closure is an executor, so plugging in an existing runtime
+/// is usually a one-liner, for example with Tokio:
+///
+/// ```rust,ignore
+/// rs4j::prelude::set_executor(|task| {
+///     RUNTIME.spawn(task);
+/// });
+/// ```
+pub trait TaskExecutor: Send + Sync + 'static {
+    /// Spawn a task. The task must be polled until it finishes.
+    fn spawn(&self, task: Task);
+}
+
+impl<F> TaskExecutor for F
+where
+    F: Fn(Task) + Send + Sync + 'static,
+{
+    fn spawn(&self, task: Task) {
+        self(task)
+    }
+}
+
+/// The default [`TaskExecutor`], which runs every task on its own thread.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadExecutor;
+
+impl TaskExecutor for ThreadExecutor {
+    fn spawn(&self, task: Task) {
+        thread::spawn(move || block_on(task));
+    }
+}
+
+static EXECUTOR: OnceLock<Box<dyn TaskExecutor>> = OnceLock::new();
+
+/// Set the [`TaskExecutor`] used for `async` methods.
+///
+/// This must be called before the first `async` method is called from Java.
+/// Returns `false` if an executor was already set (or the default one is
+/// already in use).
+pub fn set_executor(executor: impl TaskExecutor) -> bool {
+    EXECUTOR.set(Box::new(executor)).is_ok()
+}
+
+/// Get the [`TaskExecutor`] used for `async` methods.
+pub fn executor() -> &'static dyn TaskExecutor {
+    EXECUTOR.get_or_init(|| Box::new(ThreadExecutor)).as_ref()
+}
+
+/// Block the current thread until a future completes.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+
+    loop {
+        if let Poll::Ready(val) = future.as_mut().poll(&mut cx) {
+            return val;
+        }
+
+        thread::park();
+    }
+}
+
+/// The shared state for a running task.
+#[derive(Default)]
+struct TaskState {
+    cancelled: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+static NEXT_TASK: AtomicU64 = AtomicU64::new(1);
+static TASKS: OnceLock<Mutex<HashMap<u64, Arc<TaskState>>>> = OnceLock::new();
+
+fn tasks() -> &'static Mutex<HashMap<u64, Arc<TaskState>>> {
+    TASKS.get_or_init(Default::default)
+}
+
+/// How a task finished.
+enum TaskResult<T> {
+    Done(T),
+    Cancelled,
+    Panicked(String),
+}
+
+/// A future that can be cancelled from Java and catches panics.
+struct Cancellable<F> {
+    inner: Option<Pin<Box<F>>>,
+    state: Arc<TaskState>,
+}
+
+impl<F: Future> Future for Cancellable<F> {
+    type Output = TaskResult<F::Output>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        *self.state.waker.lock().unwrap() = Some(cx.waker().clone());
+
+        if self.state.cancelled.load(Ordering::Acquire) {
+            self.inner = None;
+            return Poll::Ready(TaskResult::Cancelled);
+        }
+
+        let Some(inner) = self.inner.as_mut() else {
+            return Poll::Ready(TaskResult::Cancelled);
+        };
+
+        match catch_unwind(AssertUnwindSafe(|| inner.as_mut().poll(cx))) {
+            Ok(Poll::Pending) => Poll::Pending,
+            Ok(Poll::Ready(val)) => Poll::Ready(TaskResult::Done(val)),
+
+            Err(err) => {
+                self.inner = None;
+
+                let msg = err
+                    .downcast_ref::<&str>()
+                    .map(|it| it.to_string())
+                    .or_else(|| err.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "Rust future panicked".into());
+
+                Poll::Ready(TaskResult::Panicked(msg))
+            }
+        }
+    }
+}
+
+/// Spawn a future on the [`TaskExecutor`], completing the given
+/// `CompletableFuture` with its converted output.
+///
+/// Returns the task ID, which Java uses to cancel the task.
+pub fn spawn_task<T, F, C>(env: &mut JNIEnv, future: &JObject, task: F, convert: C) -> jlong
+where
+    T: Send + 'static,
+    F: Future<Output = T> + Send + 'static,
+    C: for<'a> FnOnce(&mut JNIEnv<'a>, T) -> jobject + Send + 'static,
+{
+    let vm = env.get_java_vm().unwrap();
+    let future = env.new_global_ref(future).unwrap();
+    let id = NEXT_TASK.fetch_add(1, Ordering::Relaxed);
+    let state = Arc::new(TaskState::default());
+
+    tasks().lock().unwrap().insert(id, state.clone());
+
+    let task = Cancellable {
+        inner: Some(Box::pin(task)),
+        state,
+    };
+
+    executor().spawn(Box::pin(async move {
+        let res = task.await;
+
+        tasks().lock().unwrap().remove(&id);
+        complete_task(vm, future, res, convert);
+    }));
+
+    id as jlong
+}
+
+/// Complete a task's `CompletableFuture`. Executor threads can run any number
+/// of tasks without returning to Java, so this runs in a local reference frame.
+fn complete_task<T, C>(vm: JavaVM, future: GlobalRef, res: TaskResult<T>, convert: C)
+where
+    C: for<'a> FnOnce(&mut JNIEnv<'a>, T) -> jobject,
+{
+    let mut env = vm.attach_current_thread_as_daemon().unwrap();
+
+    env.with_local_frame(frame_capacity(), |env| -> Result<()> {
+        match res {
+            // The Java side already knows about this.
+            TaskResult::Cancelled => {}
+
+            TaskResult::Done(val) => {
+                let class = FUTURE.get(env)?;
+                let complete = FUTURE_COMPLETE.get(env, &class)?;
+                let val = unsafe { JObject::from_raw(convert(env, val)) };
+
+                unsafe {
+                    env.call_method_unchecked(
+                        &future,
+                        complete,
+                        ReturnType::Primitive(Primitive::Boolean),
+                        &[JValue::Object(&val).as_jni()],
+                    )
+                }?;
+            }
+
+            TaskResult::Panicked(msg) => {
+                let class = FUTURE.get(env)?;
+                let complete = FUTURE_COMPLETE_EXCEPTIONALLY.get(env, &class)?;
+                let err_class = RUNTIME_EXCEPTION.get(env)?;
+                let new = RUNTIME_EXCEPTION_NEW.get(env, &err_class)?;
+                let msg = env.new_string(msg)?;
+
+                let err = unsafe {
+                    env.new_object_unchecked(
+                        <&JClass>::from(err_class.as_obj()),
+                        new,
+                        &[JValue::Object(&msg).as_jni()],
+                    )
+                }?;
+
+                unsafe {
+                    env.call_method_unchecked(
+                        &future,
+                        complete,
+                        ReturnType::Primitive(Primitive::Boolean),
+                        &[JValue::Object(&err).as_jni()],
+                    )
+                }?;
+            }
+        }
+
+        Ok(())
+    })
+    .unwrap();
+}
+
+/// Cancel a running task, dropping its future. Does nothing if the task
+/// already finished.
+pub fn cancel_task(id: u64) {
+    let state = tasks().lock().unwrap().remove(&id);
+
+    if let Some(state) = state {
+        state.cancelled.store(true, Ordering::Release);
+
+        if let Some(waker) = state.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Conversions between `std::time` types and `java.time` objects.
+//!
+//! Values that don't fit on the other side are handled like this:
+//!
+//! - A negative `java.time.Duration` saturates to [`Duration::ZERO`].
+//! - A [`Duration`] longer than `Long.MAX_VALUE` seconds saturates to the
+//!   longest representable `java.time.Duration`.
+//! - A [`SystemTime`] outside of `Instant.MIN..=Instant.MAX` is clamped to that range.
+//! - A `java.time.Instant` that the platform's [`SystemTime`] can't represent
+//!   throws a `java.lang.ArithmeticException`.
+//!
+//! Converting a `null` object throws a `java.lang.NullPointerException`. When
+//! a conversion throws, it returns an [`Err`], leaving the exception pending.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use jni::{
+    JNIEnv,
+    errors::{Error, Result},
+    objects::{JClass, JObject, JValue},
+    signature::{Primitive, ReturnType},
+    sys::jobject,
+};
+
+use super::{
+    cache::{CachedClass, CachedMethod, CachedStaticMethod},
+    conv::check_non_null,
+};
+
+/// The epoch second of `java.time.Instant.MIN`.
+const INSTANT_MIN_SECONDS: i64 = -31557014167219200;
+
+/// The epoch second of `java.time.Instant.MAX`.
+const INSTANT_MAX_SECONDS: i64 = 31556889864403199;
+
+static DURATION: CachedClass = CachedClass::new("java/time/Duration");
+static DURATION_OF: CachedStaticMethod =
+    CachedStaticMethod::new("ofSeconds", "(JJ)Ljava/time/Duration;");
+static DURATION_SECONDS: CachedMethod = CachedMethod::new("getSeconds", "()J");
+static DURATION_NANOS: CachedMethod = CachedMethod::new("getNano", "()I");
+
+static INSTANT: CachedClass = CachedClass::new("java/time/Instant");
+static INSTANT_OF: CachedStaticMethod =
+    CachedStaticMethod::new("ofEpochSecond", "(JJ)Ljava/time/Instant;");
+static INSTANT_SECONDS: CachedMethod = CachedMethod::new("getEpochSecond", "()J");
+static INSTANT_NANOS: CachedMethod = CachedMethod::new("getNano", "()I");
+
+/// Create a `java.time` object from its `(seconds, nanos)` parts, with a
+/// static factory method.
+fn from_parts(
+    env: &mut JNIEnv,
+    class: &'static CachedClass,
+    method: &'static CachedStaticMethod,
+    secs: i64,
+    nanos: u32,
+) -> Result<jobject> {
+    let class = class.get(env)?;
+    let method = method.get(env, &class)?;
+    let args = [
+        JValue::Long(secs).as_jni(),
+        JValue::Long(nanos as i64).as_jni(),
+    ];
+
+    let val = unsafe {
+        env.call_static_method_unchecked(
+            <&JClass>::from(class.as_obj()),
+            method,
+            ReturnType::Object,
+            &args,
+        )
+    }?;
+
+    Ok(val.l()?.into_raw())
+}
+
+/// Get the `(seconds, nanos)` parts of a `java.time` object, which is named
+/// by `name` if it's `null`.
+fn to_parts(
+    env: &mut JNIEnv,
+    obj: &JObject,
+    name: &'static str,
+    class: &'static CachedClass,
+    secs: &'static CachedMethod,
+    nanos: &'static CachedMethod,
+) -> Result<(i64, i32)> {
+    check_non_null(env, obj, name)?;
+
+    let class = class.get(env)?;
+    let secs = secs.get(env, &class)?;
+    let nanos = nanos.get(env, &class)?;
+
+    let secs = unsafe {
+        env.call_method_unchecked(obj, secs, ReturnType::Primitive(Primitive::Long), &[])
+    };
+
+    let nanos = unsafe {
+        env.call_method_unchecked(obj, nanos, ReturnType::Primitive(Primitive::Int), &[])
+    };
+
+    Ok((secs?.j()?, nanos?.i()?))
+}
+
+/// Split a `(seconds, nanos)` pair from Java into a sign and a [`Duration`].
+fn java_parts_to_duration(secs: i64, nanos: i32) -> (bool, Duration) {
+    // Java always normalizes the nano part to `0..1_000_000_000`,
+    // so `secs < 0` is the only way for a value to be negative.
+    if secs >= 0 {
+        (false, Duration::new(secs as u64, nanos as u32))
+    } else {
+        let total = Duration::new(secs.unsigned_abs(), 0) - Duration::new(0, nanos as u32);
+
+        (true, total)
+    }
+}
+
+/// Convert a [`Duration`] to a `java.time.Duration`.
+pub fn duration_to_jobject(env: &mut JNIEnv, val: Duration) -> Result<jobject> {
+    let (secs, nanos) = match i64::try_from(val.as_secs()) {
+        Ok(secs) => (secs, val.subsec_nanos()),
+        Err(_) => (i64::MAX, 999_999_999),
+    };
+
+    from_parts(env, &DURATION, &DURATION_OF, secs, nanos)
+}
+
+/// Convert a `java.time.Duration` to a [`Duration`].
+pub fn duration_from_jobject(env: &mut JNIEnv, obj: &JObject) -> Result<Duration> {
+    let (secs, nanos) = to_parts(
+        env,
+        obj,
+        "java.time.Duration",
+        &DURATION,
+        &DURATION_SECONDS,
+        &DURATION_NANOS,
+    )?;
+
+    match java_parts_to_duration(secs, nanos) {
+        (false, val) => Ok(val),
+        (true, _) => Ok(Duration::ZERO),
+    }
+}
+
+/// Convert a [`SystemTime`] to a `java.time.Instant`.
+pub fn system_time_to_jobject(env: &mut JNIEnv, val: SystemTime) -> Result<jobject> {
+    let (secs, nanos) = match val.duration_since(UNIX_EPOCH) {
+        Ok(since) => match i64::try_from(since.as_secs()) {
+            Ok(secs) if secs <= INSTANT_MAX_SECONDS => (secs, since.subsec_nanos()),
+            _ => (INSTANT_MAX_SECONDS, 999_999_999),
+        },
+
+        Err(err) => {
+            let before = err.duration();
+            let secs = i64::try_from(before.as_secs()).unwrap_or(i64::MAX);
+
+            if before.subsec_nanos() == 0 {
+                (secs.saturating_neg(), 0)
+            } else {
+                (
+                    secs.saturating_neg().saturating_sub(1),
+                    1_000_000_000 - before.subsec_nanos(),
+                )
+            }
+        }
+    };
+
+    let (secs, nanos) = if secs < INSTANT_MIN_SECONDS {
+        (INSTANT_MIN_SECONDS, 0)
+    } else {
+        (secs, nanos)
+    };
+
+    from_parts(env, &INSTANT, &INSTANT_OF, secs, nanos)
+}
+
+/// Convert a `java.time.Instant` to a [`SystemTime`].
+pub fn system_time_from_jobject(env: &mut JNIEnv, obj: &JObject) -> Result<SystemTime> {
+    let (secs, nanos) = to_parts(
+        env,
+        obj,
+        "java.time.Instant",
+        &INSTANT,
+        &INSTANT_SECONDS,
+        &INSTANT_NANOS,
+    )?;
+
+    let time = match java_parts_to_duration(secs, nanos) {
+        (false, since) => UNIX_EPOCH.checked_add(since),
+        (true, before) => UNIX_EPOCH.checked_sub(before),
+    };
+
+    match time {
+        Some(time) => Ok(time),
+
+        None => {
+            env.throw_new(
+                "java/lang/ArithmeticException",
+                "Instant cannot be represented as a std::time::SystemTime on this platform",
+            )?;
+
+            Err(Error::JavaException)
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! The process-wide [`JavaVM`], for getting a [`JNIEnv`] on any thread.
+//!
+//! The VM is captured by the first generated native that's called, or in
+//! `JNI_OnLoad` if the library calls [`on_load`] from it. Libraries that start
+//! the JVM themselves can hand it over with [`set_java_vm`].
+
+use std::sync::{
+    OnceLock,
+    atomic::{AtomicI32, Ordering},
+};
+
+use jni::{
+    AttachGuard, JNIEnv, JavaVM,
+    errors::{Error, JniError, Result},
+    sys::{self, JNI_VERSION_1_8, jint},
+};
+
+use super::cache::{clear_cache, remember_class_loader};
+
+static JAVA_VM: OnceLock<JavaVM> = OnceLock::new();
+
+static FRAME_CAPACITY: AtomicI32 = AtomicI32::new(16);
+
+/// Set `rs4j` up when the JVM loads the library, remembering the
+/// [`JavaVM`]. Call this from your own `JNI_OnLoad`, or define one that only
+/// does this with [`jni_onload!`](crate::jni_onload). Returns the JNI version
+/// to give back to the JVM.
+///
+/// # Safety
+///
+/// `vm` has to be a valid `JavaVM` pointer.
+pub unsafe fn on_load(vm: *mut sys::JavaVM) -> jint {
+    if let Ok(vm) = unsafe { JavaVM::from_raw(vm) } {
+        if let Ok(mut env) = vm.get_env() {
+            remember_class_loader(&mut env);
+        }
+
+        set_java_vm(vm);
+    }
+
+    JNI_VERSION_1_8
+}
+
+/// Clean up when the library's class loader is collected, dropping
+/// everything in the [class and member caches](super::cache). Call this from
+/// your own `JNI_OnUnload`, or define one with
+/// [`jni_onload!`](crate::jni_onload).
+///
+/// The caches keep that class loader alive while they hold its classes, so
+/// this only runs if they were cleared with [`clear_cache`] first.
+pub fn on_unload() {
+    clear_cache();
+}
+
+/// Set the [`JavaVM`], if it isn't known yet. There's only ever one per
+/// process, so later calls do nothing.
+pub fn set_java_vm(vm: JavaVM) {
+    let _ = JAVA_VM.set(vm);
+}
+
+/// Remember the [`JavaVM`] an env belongs to, and the class loader that
+/// loaded the library, if they aren't known yet. Every generated native calls
+/// this first, so once both are known, it doesn't lock anything.
+pub fn remember_java_vm(env: &JNIEnv) {
+    if JAVA_VM.get().is_none()
+        && let Ok(vm) = env.get_java_vm()
+    {
+        set_java_vm(vm);
+    }
+
+    // Only used on this thread, which Java called into.
+    remember_class_loader(&mut unsafe { env.unsafe_clone() });
+}
+
+/// Get the [`JavaVM`]. This fails if Java hasn't loaded the library or
+/// called into it yet.
+pub fn java_vm() -> Result<&'static JavaVM> {
+    JAVA_VM.get().ok_or(Error::NullPtr("JavaVM"))
+}
+
+/// Attach the current thread to the JVM as a daemon, which doesn't keep the
+/// JVM running, and stays attached until it exits. Does nothing more if the
+/// thread is already attached.
+pub fn attach_daemon() -> Result<JNIEnv<'static>> {
+    java_vm()?.attach_current_thread_as_daemon()
+}
+
+/// Attach the current thread to the JVM until the guard is dropped. If it was
+/// already attached, dropping the guard leaves it that way.
+pub fn attach_scoped() -> Result<AttachGuard<'static>> {
+    java_vm()?.attach_current_thread()
+}
+
+/// Get an env for the current thread, from anywhere. Threads that aren't
+/// attached yet (like ones Rust started) are attached as daemons.
+pub fn current_env() -> Result<JNIEnv<'static>> {
+    let vm = java_vm()?;
+
+    match vm.get_env() {
+        Ok(env) => Ok(env),
+        Err(Error::JniCall(JniError::ThreadDetached)) => vm.attach_current_thread_as_daemon(),
+        Err(err) => Err(err),
+    }
+}
+
+/// Run a closure with the current thread's env, in a new local reference
+/// frame with room for at least `capacity` references. Local references the
+/// closure makes are freed when it returns, so it can be called in a loop
+/// from a thread that never returns to Java.
+pub fn with_local_frame<T, E, F>(capacity: i32, f: F) -> std::result::Result<T, E>
+where
+    F: FnOnce(&mut JNIEnv) -> std::result::Result<T, E>,
+    E: From<Error>,
+{
+    current_env()?.with_local_frame(capacity, f)
+}
+
+/// Get the capacity of the local reference frames that conversions and
+/// completing `async` methods run in. Defaults to 16.
+pub fn frame_capacity() -> i32 {
+    FRAME_CAPACITY.load(Ordering::Relaxed)
+}
+
+/// Set the capacity of the local reference frames that conversions and
+/// completing `async` methods run in. Frames grow past it if they need to, but
+/// reserving enough up front saves the JVM some work.
+pub fn set_frame_capacity(capacity: i32) {
+    FRAME_CAPACITY.store(capacity.max(1), Ordering::Relaxed);
+}
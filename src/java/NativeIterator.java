@@ -0,0 +1,74 @@
+package org.stardustmodding.rs4j.util;
+
+import java.util.Iterator;
+import java.util.NoSuchElementException;
+import java.util.Spliterator;
+import java.util.Spliterators;
+import java.util.function.Function;
+import java.util.function.LongFunction;
+import java.util.stream.Stream;
+import java.util.stream.StreamSupport;
+
+public class NativeIterator<T> implements Iterator<T>, AutoCloseable {
+    private static final Object DONE = new Object();
+
+    private long ptr;
+    private Object next = DONE;
+    private boolean fetched = false;
+    private final Function<Object, T> convert;
+
+    @SuppressWarnings("unchecked")
+    public NativeIterator(long ptr) {
+        this(ptr, it -> (T) it);
+    }
+
+    private NativeIterator(long ptr, Function<Object, T> convert) {
+        this.ptr = ptr;
+        this.convert = convert;
+    }
+
+    public static <T> NativeIterator<T> ofPointers(long ptr, LongFunction<T> convert) {
+        return new NativeIterator<>(ptr, it -> convert.apply((Long) it));
+    }
+
+    private static native Object nextJni(long ptr, Object done);
+
+    private static native void freeJni(long ptr);
+
+    @Override
+    public synchronized boolean hasNext() {
+        if (!fetched && ptr != 0) {
+            next = nextJni(ptr, DONE);
+            fetched = true;
+
+            if (next == DONE) close();
+        }
+
+        return next != DONE;
+    }
+
+    @Override
+    public synchronized T next() {
+        if (!hasNext()) throw new NoSuchElementException();
+
+        Object val = next;
+
+        next = DONE;
+        fetched = false;
+
+        return convert.apply(val);
+    }
+
+    public Stream<T> stream() {
+        return StreamSupport.stream(Spliterators.spliteratorUnknownSize(this, Spliterator.ORDERED), false)
+                .onClose(this::close);
+    }
+
+    @Override
+    public synchronized void close() {
+        if (ptr != 0) {
+            freeJni(ptr);
+            ptr = 0;
+        }
+    }
+}
@@ -0,0 +1,66 @@
+package org.stardustmodding.rs4j.util
+
+import java.util.Spliterator
+import java.util.Spliterators
+import java.util.stream.Stream
+import java.util.stream.StreamSupport
+
+class NativeIterator<T> private constructor(private var ptr: Long, private val convert: (Any?) -> T) :
+    Iterator<T>, AutoCloseable {
+    private var next: Any? = DONE
+    private var fetched = false
+
+    @Suppress("UNCHECKED_CAST")
+    constructor(ptr: Long) : this(ptr, { it as T })
+
+    @Synchronized
+    override fun hasNext(): Boolean {
+        if (!fetched && ptr != 0L) {
+            next = nextJni(ptr, DONE)
+            fetched = true
+
+            if (next === DONE) close()
+        }
+
+        return next !== DONE
+    }
+
+    @Synchronized
+    override fun next(): T {
+        if (!hasNext()) throw NoSuchElementException()
+
+        val value = next
+
+        next = DONE
+        fetched = false
+
+        return convert(value)
+    }
+
+    fun stream(): Stream<T> {
+        return StreamSupport.stream(Spliterators.spliteratorUnknownSize(this, Spliterator.ORDERED), false)
+            .onClose(this::close)
+    }
+
+    @Synchronized
+    override fun close() {
+        if (ptr != 0L) {
+            freeJni(ptr)
+            ptr = 0
+        }
+    }
+
+    companion object {
+        private val DONE = Any()
+
+        @JvmStatic
+        private external fun nextJni(ptr: Long, done: Any): Any?
+
+        @JvmStatic
+        private external fun freeJni(ptr: Long)
+
+        fun <T> ofPointers(ptr: Long, convert: (Long) -> T): NativeIterator<T> {
+            return NativeIterator(ptr) { convert(it as Long) }
+        }
+    }
+}
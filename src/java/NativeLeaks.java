@@ -0,0 +1,27 @@
+package org.stardustmodding.rs4j.util;
+
+// The objects made for Java that haven't been freed, when bindings are generated with `track_leaks`.
+public class NativeLeaks {
+    private static native String reportJni();
+
+    // Get the number of objects that are still alive.
+    public static native long liveCount();
+
+    // Keep the stack trace of every object made from now on. This is slow.
+    public static native void keepTraces(boolean enable);
+
+    // Get the objects that are still alive, with their number per class and where they were made.
+    public static String report() {
+        return reportJni();
+    }
+
+    // Print the report to stderr.
+    public static void print() {
+        System.err.print(report());
+    }
+
+    // Print the report when the JVM shuts down.
+    public static void printOnExit() {
+        Runtime.getRuntime().addShutdownHook(new Thread(NativeLeaks::print, "rs4j-leaks"));
+    }
+}
@@ -0,0 +1,27 @@
+package org.stardustmodding.rs4j.util
+
+// The objects made for Java that haven't been freed, when bindings are generated with `track_leaks`.
+object NativeLeaks {
+    @JvmStatic
+    private external fun reportJni(): String
+
+    // Get the number of objects that are still alive.
+    @JvmStatic
+    external fun liveCount(): Long
+
+    // Keep the stack trace of every object made from now on. This is slow.
+    @JvmStatic
+    external fun keepTraces(enable: Boolean)
+
+    // Get the objects that are still alive, with their number per class and where they were made.
+    @JvmStatic
+    fun report(): String = reportJni()
+
+    // Print the report to stderr.
+    @JvmStatic
+    fun print() = System.err.print(report())
+
+    // Print the report when the JVM shuts down.
+    @JvmStatic
+    fun printOnExit() = Runtime.getRuntime().addShutdownHook(Thread({ print() }, "rs4j-leaks"))
+}
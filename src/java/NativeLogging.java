@@ -0,0 +1,14 @@
+package org.stardustmodding.rs4j.util;
+
+// Controls forwarding Rust `log` records to SLF4J or java.util.logging.
+// The library has to be built with rs4j's `log` feature.
+public class NativeLogging {
+    // Start forwarding records, if the library hasn't set up a logger itself.
+    // Returns false if there already is one.
+    public static native boolean install();
+
+    // Set the most verbose level that's forwarded: off, error, warn, info, debug or trace.
+    public static native void setMaxLevel(String level);
+
+    public static native String getMaxLevel();
+}
@@ -0,0 +1,50 @@
+package org.stardustmodding.rs4j.util;
+
+import java.util.ArrayList;
+import java.util.List;
+
+// Call counts and timings for natives, when bindings are generated with `instrument`.
+public class NativeStats {
+    // The counts for one native, like `com.example.MyStruct.add`.
+    public record Entry(String method, long calls, long totalNanos) {
+        public long averageNanos() {
+            return calls == 0 ? 0 : totalNanos / calls;
+        }
+
+        @Override
+        public String toString() {
+            return method + ": " + calls + " calls, " + totalNanos / 1000 + "us total, " + averageNanos() + "ns avg";
+        }
+    }
+
+    private static native Object[] snapshotJni();
+
+    // Set every count back to zero.
+    public static native void reset();
+
+    // Get the counts for every native that's been called, the ones with the most time spent in them first.
+    public static List<Entry> snapshot() {
+        Object[] data = snapshotJni();
+        String[] names = (String[]) data[0];
+        long[] calls = (long[]) data[1];
+        long[] nanos = (long[]) data[2];
+        List<Entry> entries = new ArrayList<>();
+
+        for (int i = 0; i < names.length; i++) {
+            entries.add(new Entry(names[i], calls[i], nanos[i]));
+        }
+
+        return entries;
+    }
+
+    // Get the counts as a table, one native per line.
+    public static String report() {
+        StringBuilder out = new StringBuilder();
+
+        for (Entry entry : snapshot()) {
+            out.append(entry).append('\n');
+        }
+
+        return out.toString();
+    }
+}
@@ -0,0 +1,33 @@
+package org.stardustmodding.rs4j.util
+
+// Call counts and timings for natives, when bindings are generated with `instrument`.
+object NativeStats {
+    // The counts for one native, like `com.example.MyStruct.add`.
+    data class Entry(val method: String, val calls: Long, val totalNanos: Long) {
+        val averageNanos: Long
+            get() = if (calls == 0L) 0 else totalNanos / calls
+
+        override fun toString(): String =
+            "$method: $calls calls, ${totalNanos / 1000}us total, ${averageNanos}ns avg"
+    }
+
+    @JvmStatic
+    private external fun snapshotJni(): Array<Any>
+
+    // Set every count back to zero.
+    @JvmStatic
+    external fun reset()
+
+    // Get the counts for every native that's been called, the ones with the most time spent in them first.
+    fun snapshot(): List<Entry> {
+        val data = snapshotJni()
+        val names = data[0] as Array<*>
+        val calls = data[1] as LongArray
+        val nanos = data[2] as LongArray
+
+        return names.indices.map { Entry(names[it] as String, calls[it], nanos[it]) }
+    }
+
+    // Get the counts as a table, one native per line.
+    fun report(): String = snapshot().joinToString("") { "$it\n" }
+}
@@ -1,6 +1,7 @@
 package org.stardustmodding.rs4j.util;
 
-import java.lang.reflect.ParameterizedType;
+import java.util.concurrent.CompletableFuture;
+import java.util.function.Function;
 
 public class NativeTools {
     public static native String getString(long ptr);
@@ -21,20 +22,31 @@ public class NativeTools {
 
     public static native short getShort(long ptr);
 
-    private static native Object getObjectJni(long ptr, String className);
+    private static native Object getObjectJni(long ptr, Class<?> type);
 
-    @SuppressWarnings("unchecked")
-    public static <T> T getObject(long ptr) {
-        return (T) getObjectJni(ptr, new GetTypeParent<T>().getGenericName());
+    private static native void cancelTask(long task);
+
+    public static <T> CompletableFuture<T> awaitTask(CompletableFuture<T> future, long task) {
+        future.whenComplete((value, error) -> {
+            if (future.isCancelled()) cancelTask(task);
+        });
+
+        return future;
+    }
+
+    public static <R, T> CompletableFuture<T> awaitTask(CompletableFuture<R> future, long task, Function<R, T> convert) {
+        CompletableFuture<T> mapped = awaitTask(future, task).thenApply(convert);
+
+        mapped.whenComplete((value, error) -> {
+            if (mapped.isCancelled()) future.cancel(false);
+        });
+
+        return mapped;
     }
 
-    private static class GetTypeParent<T> {
-        // FIXME: Does this even work? Isn't there type erasure during compilation?
-        // Investigate.
-        @SuppressWarnings("unchecked")
-        public String getGenericName() {
-            return ((Class<T>) ((ParameterizedType) getClass()
-                    .getGenericSuperclass()).getActualTypeArguments()[0]).getName().replaceAll(".", "/");
-        }
+    // Wraps a pointer in an object of a generated class, which takes ownership of it,
+    // like the class's own from(long).
+    public static <T extends NativeClass> T getObject(long ptr, Class<T> type) {
+        return type.cast(getObjectJni(ptr, type));
     }
 }
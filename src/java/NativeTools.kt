@@ -1,5 +1,7 @@
 package org.stardustmodding.rs4j.util
 
+import java.util.concurrent.CompletableFuture
+
 object NativeTools {
     external fun getString(ptr: Long): String?
     external fun getBool(ptr: Long): Boolean
@@ -11,9 +13,25 @@ object NativeTools {
     external fun getChar(ptr: Long): Char
     external fun getShort(ptr: Long): Short
 
-    external fun getObjectJni(ptr: Long, className: String): Any?
+    private external fun getObjectJni(ptr: Long, type: Class<*>): Any?
+
+    private external fun cancelTask(task: Long)
+
+    fun <T> awaitTask(future: CompletableFuture<T>, task: Long): CompletableFuture<T> {
+        future.whenComplete { _, _ ->
+            if (future.isCancelled) cancelTask(task)
+        }
+
+        return future
+    }
+
+    // Wraps a pointer in an object of a generated class, which takes ownership of it,
+    // like the class's own from(ptr).
+    fun <T : NativeClass> getObject(ptr: Long, type: Class<T>): T? {
+        return type.cast(getObjectJni(ptr, type))
+    }
 
-    inline fun <reified T> getObject(ptr: Long): T? {
-        return getObjectJni(ptr, T::class.java.canonicalName) as T?
+    inline fun <reified T : NativeClass> getObject(ptr: Long): T? {
+        return getObject(ptr, T::class.java)
     }
 }
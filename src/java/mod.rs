@@ -16,6 +16,18 @@ pub mod java {
 
     /// The native class
     pub const NATIVE_CLASS: &str = include_str!("NativeClass.java");
+
+    /// An iterator backed by a Rust iterator
+    pub const NATIVE_ITERATOR: &str = include_str!("NativeIterator.java");
+
+    /// Controls for forwarding Rust logs
+    pub const NATIVE_LOGGING: &str = include_str!("NativeLogging.java");
+
+    /// Call counts and timings for instrumented natives
+    pub const NATIVE_STATS: &str = include_str!("NativeStats.java");
+
+    /// Reports of the objects made for Java that are still alive
+    pub const NATIVE_LEAKS: &str = include_str!("NativeLeaks.java");
 }
 
 /// Kotlin variants of the shared code.
@@ -34,4 +46,16 @@ pub mod kotlin {
 
     /// The native class
     pub const NATIVE_CLASS: &str = include_str!("NativeClass.kt");
+
+    /// An iterator backed by a Rust iterator
+    pub const NATIVE_ITERATOR: &str = include_str!("NativeIterator.kt");
+
+    /// Controls for forwarding Rust logs
+    pub const NATIVE_LOGGING: &str = include_str!("NativeLogging.kt");
+
+    /// Call counts and timings for instrumented natives
+    pub const NATIVE_STATS: &str = include_str!("NativeStats.kt");
+
+    /// Reports of the objects made for Java that are still alive
+    pub const NATIVE_LEAKS: &str = include_str!("NativeLeaks.kt");
 }
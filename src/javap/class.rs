@@ -0,0 +1,614 @@
+//! Parsing `.class` files.
+
+use anyhow::{Result, bail};
+
+use crate::if_else;
+
+/// The class or member is public.
+pub const ACC_PUBLIC: u16 = 0x0001;
+
+/// The method is static.
+pub const ACC_STATIC: u16 = 0x0008;
+
+/// The method is a bridge, generated by the compiler.
+pub const ACC_BRIDGE: u16 = 0x0040;
+
+/// The class is an interface.
+pub const ACC_INTERFACE: u16 = 0x0200;
+
+/// The class or method is abstract.
+pub const ACC_ABSTRACT: u16 = 0x0400;
+
+/// The class or member was generated by the compiler.
+pub const ACC_SYNTHETIC: u16 = 0x1000;
+
+/// A parsed `.class` file, with only what's needed for bindings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClassFile {
+    /// The class's access flags.
+    pub access: u16,
+
+    /// The JNI name of the class, like `java/util/ArrayList`.
+    pub name: String,
+
+    /// The class's generic signature, if it has one.
+    pub signature: Option<String>,
+
+    /// The class's methods, including constructors (`<init>`).
+    pub methods: Vec<MethodInfo>,
+}
+
+/// A method in a [`ClassFile`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodInfo {
+    /// The method's access flags.
+    pub access: u16,
+
+    /// The method's name.
+    pub name: String,
+
+    /// The method's descriptor, like `(ILjava/lang/Object;)V`.
+    pub descriptor: String,
+
+    /// The method's generic signature, if it has one.
+    pub signature: Option<String>,
+
+    /// The parameter names, if the class was compiled with `-parameters`.
+    pub params: Vec<String>,
+}
+
+/// A type in a descriptor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Descriptor {
+    /// A primitive (or `void`), by its descriptor character.
+    Primitive(char),
+
+    /// A class, by its JNI name.
+    Class(String),
+
+    /// An array.
+    Array(Box<Descriptor>),
+}
+
+/// An entry in the constant pool. Only the ones we read are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Constant {
+    Utf8(String),
+    Class(usize),
+    Other,
+}
+
+/// Reads big-endian values from a class file.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl Reader<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8]> {
+        match self.data.get(self.pos..self.pos + len) {
+            Some(it) => {
+                self.pos += len;
+                Ok(it)
+            }
+
+            None => bail!("Unexpected end of class file!"),
+        }
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        let it = self.bytes(2)?;
+
+        Ok(u16::from_be_bytes([it[0], it[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        let it = self.bytes(4)?;
+
+        Ok(u32::from_be_bytes([it[0], it[1], it[2], it[3]]))
+    }
+}
+
+impl ClassFile {
+    /// Parse a class file.
+    pub fn parse(data: &[u8]) -> Result<Self> {
+        let mut data = Reader { data, pos: 0 };
+
+        if data.u32()? != 0xcafebabe {
+            bail!("Not a class file!");
+        }
+
+        // The minor and major versions.
+        data.bytes(4)?;
+
+        let pool = Self::constant_pool(&mut data)?;
+        let access = data.u16()?;
+        let name = Self::class_at(&pool, data.u16()? as usize)?;
+
+        // The super class and interfaces.
+        data.u16()?;
+
+        let interfaces = data.u16()? as usize;
+
+        data.bytes(interfaces * 2)?;
+
+        // Fields aren't callable, so they're skipped.
+        for _ in 0..data.u16()? {
+            data.bytes(6)?;
+            Self::attributes(&mut data, &pool)?;
+        }
+
+        let mut methods = Vec::new();
+
+        for _ in 0..data.u16()? {
+            let access = data.u16()?;
+            let name = Self::utf8_at(&pool, data.u16()? as usize)?;
+            let descriptor = Self::utf8_at(&pool, data.u16()? as usize)?;
+            let attrs = Self::attributes(&mut data, &pool)?;
+
+            methods.push(MethodInfo {
+                access,
+                name,
+                descriptor,
+                signature: attrs.signature,
+                params: attrs.params,
+            });
+        }
+
+        let attrs = Self::attributes(&mut data, &pool)?;
+
+        Ok(Self {
+            access,
+            name,
+            signature: attrs.signature,
+            methods,
+        })
+    }
+
+    /// Read the constant pool. Index 0 isn't used, and `long`s and `double`s
+    /// take up two entries.
+    fn constant_pool(data: &mut Reader) -> Result<Vec<Constant>> {
+        let count = data.u16()? as usize;
+        let mut pool = vec![Constant::Other];
+
+        while pool.len() < count {
+            let tag = data.u8()?;
+
+            let (constant, size) = match tag {
+                1 => {
+                    let len = data.u16()? as usize;
+
+                    (Constant::Utf8(decode_utf8(data.bytes(len)?)), 1)
+                }
+
+                7 => (Constant::Class(data.u16()? as usize), 1),
+                8 | 16 | 19 | 20 => (data.bytes(2).map(|_| Constant::Other)?, 1),
+                15 => (data.bytes(3).map(|_| Constant::Other)?, 1),
+                3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => (data.bytes(4).map(|_| Constant::Other)?, 1),
+                5 | 6 => (data.bytes(8).map(|_| Constant::Other)?, 2),
+                _ => bail!("Unknown constant pool tag: {tag}!"),
+            };
+
+            pool.push(constant);
+
+            if size == 2 {
+                pool.push(Constant::Other);
+            }
+        }
+
+        Ok(pool)
+    }
+
+    /// Get a string from the constant pool.
+    fn utf8_at(pool: &[Constant], index: usize) -> Result<String> {
+        match pool.get(index) {
+            Some(Constant::Utf8(it)) => Ok(it.clone()),
+            _ => bail!("Constant pool entry {index} isn't a string!"),
+        }
+    }
+
+    /// Get a class name from the constant pool.
+    fn class_at(pool: &[Constant], index: usize) -> Result<String> {
+        match pool.get(index) {
+            Some(Constant::Class(it)) => Self::utf8_at(pool, *it),
+            _ => bail!("Constant pool entry {index} isn't a class!"),
+        }
+    }
+
+    /// Read a list of attributes, keeping the ones we use.
+    fn attributes(data: &mut Reader, pool: &[Constant]) -> Result<Attributes> {
+        let mut attrs = Attributes::default();
+
+        for _ in 0..data.u16()? {
+            let name = Self::utf8_at(pool, data.u16()? as usize)?;
+            let len = data.u32()? as usize;
+            let mut attr = Reader {
+                data: data.bytes(len)?,
+                pos: 0,
+            };
+
+            match name.as_str() {
+                "Signature" => {
+                    attrs.signature = Some(Self::utf8_at(pool, attr.u16()? as usize)?);
+                }
+
+                "MethodParameters" => {
+                    for _ in 0..attr.u8()? {
+                        let name = attr.u16()? as usize;
+
+                        // Unnamed parameters have index 0.
+                        attrs.params.push(if_else!(
+                            name == 0,
+                            String::new(),
+                            Self::utf8_at(pool, name)?
+                        ));
+
+                        attr.u16()?;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+
+        Ok(attrs)
+    }
+}
+
+/// The attributes of a class or method that we read.
+#[derive(Debug, Clone, Default)]
+struct Attributes {
+    signature: Option<String>,
+    params: Vec<String>,
+}
+
+/// Decode Java's modified UTF-8, where `\0` takes two bytes and characters
+/// outside the BMP are surrogate pairs.
+fn decode_utf8(data: &[u8]) -> String {
+    let mut units = Vec::new();
+    let mut iter = data.iter().map(|it| *it as u16);
+
+    while let Some(a) = iter.next() {
+        let unit = match a {
+            0..0x80 => a,
+            0xc0..0xe0 => ((a & 0x1f) << 6) | (iter.next().unwrap_or(0) & 0x3f),
+
+            _ => {
+                let b = iter.next().unwrap_or(0) & 0x3f;
+                let c = iter.next().unwrap_or(0) & 0x3f;
+
+                ((a & 0x0f) << 12) | (b << 6) | c
+            }
+        };
+
+        units.push(unit);
+    }
+
+    String::from_utf16_lossy(&units)
+}
+
+impl MethodInfo {
+    /// Get the parameter and return types from the descriptor.
+    pub fn types(&self) -> Result<(Vec<Descriptor>, Descriptor)> {
+        let Some(rest) = self.descriptor.strip_prefix("(") else {
+            bail!("Invalid method descriptor: {}", self.descriptor);
+        };
+
+        let mut chars = rest.chars().peekable();
+        let mut params = Vec::new();
+
+        while chars.peek() != Some(&')') {
+            params.push(Descriptor::parse(&mut chars, &self.descriptor)?);
+        }
+
+        chars.next();
+
+        let ret = Descriptor::parse(&mut chars, &self.descriptor)?;
+
+        Ok((params, ret))
+    }
+}
+
+impl Descriptor {
+    /// Parse a type from a descriptor.
+    fn parse(chars: &mut impl Iterator<Item = char>, desc: &str) -> Result<Self> {
+        Ok(match chars.next() {
+            Some(it @ ('B' | 'C' | 'D' | 'F' | 'I' | 'J' | 'S' | 'Z' | 'V')) => Self::Primitive(it),
+            Some('[') => Self::Array(Box::new(Self::parse(chars, desc)?)),
+            Some('L') => Self::Class(chars.take_while(|it| *it != ';').collect()),
+            _ => bail!("Invalid method descriptor: {desc}"),
+        })
+    }
+}
+
+/// Turn the generic signature of a method into Java syntax, like
+/// `<T> T get(int)`, for comments. This isn't strict, since it's only read
+/// by people.
+pub fn pretty_method_signature(name: &str, sig: &str) -> String {
+    let chars = sig.chars().collect::<Vec<_>>();
+    let mut pos = 0;
+    let generics = pretty_generics(&chars, &mut pos);
+    let generics = if_else!(generics.is_empty(), generics, format!("{generics} "));
+    let mut params = Vec::new();
+
+    pos += 1;
+
+    while pos < chars.len() && chars[pos] != ')' {
+        params.push(pretty_type(&chars, &mut pos));
+    }
+
+    pos += 1;
+
+    let ret = pretty_type(&chars, &mut pos);
+
+    format!("{generics}{ret} {name}({})", params.join(", "))
+}
+
+/// Turn the generic signature of a class into Java syntax, like
+/// `ArrayList<E> extends AbstractList<E> implements List<E>`, for comments.
+pub fn pretty_class_signature(name: &str, sig: &str, interface: bool) -> String {
+    let chars = sig.chars().collect::<Vec<_>>();
+    let mut pos = 0;
+    let generics = pretty_generics(&chars, &mut pos);
+    let mut supers = Vec::new();
+
+    while pos < chars.len() {
+        supers.push(pretty_type(&chars, &mut pos));
+    }
+
+    let mut out = format!("{name}{generics}");
+
+    // Interfaces always have `Object` as their superclass.
+    if !supers.is_empty() && supers[0] != "Object" {
+        out.push_str(&format!(" extends {}", supers[0]));
+    }
+
+    if supers.len() > 1 {
+        let keyword = if_else!(interface, "extends", "implements");
+
+        out.push_str(&format!(" {keyword} {}", supers[1..].join(", ")));
+    }
+
+    out
+}
+
+/// Turn the type parameters at the start of a generic signature into Java
+/// syntax, like `<T extends Number>`.
+fn pretty_generics(chars: &[char], pos: &mut usize) -> String {
+    if chars.get(*pos) != Some(&'<') {
+        return String::new();
+    }
+
+    *pos += 1;
+
+    let mut params = Vec::new();
+
+    while *pos < chars.len() && chars[*pos] != '>' {
+        let name = chars[*pos..]
+            .iter()
+            .take_while(|it| **it != ':')
+            .collect::<String>();
+
+        *pos += name.chars().count();
+
+        let mut bounds = Vec::new();
+
+        // The class bound can be empty, leaving just the interface ones.
+        while chars.get(*pos) == Some(&':') {
+            *pos += 1;
+
+            if chars.get(*pos) != Some(&':') {
+                bounds.push(pretty_type(chars, pos));
+            }
+        }
+
+        bounds.retain(|it| it != "Object");
+
+        params.push(if_else!(
+            bounds.is_empty(),
+            name.clone(),
+            format!("{name} extends {}", bounds.join(" & "))
+        ));
+    }
+
+    *pos += 1;
+
+    format!("<{}>", params.join(", "))
+}
+
+/// Turn one type in a generic signature into Java syntax.
+fn pretty_type(chars: &[char], pos: &mut usize) -> String {
+    let Some(it) = chars.get(*pos) else {
+        return String::new();
+    };
+
+    *pos += 1;
+
+    match it {
+        'B' => "byte".into(),
+        'C' => "char".into(),
+        'D' => "double".into(),
+        'F' => "float".into(),
+        'I' => "int".into(),
+        'J' => "long".into(),
+        'S' => "short".into(),
+        'Z' => "boolean".into(),
+        'V' => "void".into(),
+        '*' => "?".into(),
+        '+' => format!("? extends {}", pretty_type(chars, pos)),
+        '-' => format!("? super {}", pretty_type(chars, pos)),
+        '[' => format!("{}[]", pretty_type(chars, pos)),
+
+        'T' => {
+            let name = chars[*pos..]
+                .iter()
+                .take_while(|it| **it != ';')
+                .collect::<String>();
+
+            *pos += name.chars().count() + 1;
+            name
+        }
+
+        'L' => {
+            let mut out = String::new();
+
+            while let Some(it) = chars.get(*pos) {
+                *pos += 1;
+
+                match it {
+                    ';' => break,
+                    '/' => out.clear(),
+                    '.' => out.push('.'),
+
+                    '<' => {
+                        let mut args = Vec::new();
+
+                        while *pos < chars.len() && chars[*pos] != '>' {
+                            args.push(pretty_type(chars, pos));
+                        }
+
+                        *pos += 1;
+                        out.push_str(&format!("<{}>", args.join(", ")));
+                    }
+
+                    it => out.push(*it),
+                }
+            }
+
+            out.replace("$", ".")
+        }
+
+        // Throws clauses aren't shown.
+        '^' => {
+            *pos = chars.len();
+            String::new()
+        }
+
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use super::*;
+
+    const SHAPES: &[u8] = include_bytes!("../../tests/fixtures/javap/Shapes.class");
+
+    fn method<'a>(class: &'a ClassFile, name: &str, descriptor: &str) -> &'a MethodInfo {
+        class
+            .methods
+            .iter()
+            .find(|it| it.name == name && it.descriptor == descriptor)
+            .unwrap()
+    }
+
+    #[test]
+    fn parse_class() {
+        let class = ClassFile::parse(SHAPES).unwrap();
+
+        assert_eq!(class.name, "fixtures/Shapes");
+        assert_eq!(class.access & ACC_PUBLIC, ACC_PUBLIC);
+        assert_eq!(class.signature, None);
+
+        let area = method(&class, "area", "(DD)D");
+
+        assert_eq!(area.params, ["width", "height"]);
+
+        assert_eq!(
+            area.types().unwrap(),
+            (
+                vec![Descriptor::Primitive('D'), Descriptor::Primitive('D')],
+                Descriptor::Primitive('D')
+            )
+        );
+
+        assert_eq!(method(&class, "area", "(I)I").params, ["side"]);
+
+        assert_eq!(
+            method(&class, "corners", "()[I").types().unwrap().1,
+            Descriptor::Array(Box::new(Descriptor::Primitive('I')))
+        );
+
+        let pick = method(&class, "pick", "(Lfixtures/Shapes;)Lfixtures/Shapes;");
+
+        assert_eq!(
+            pick.signature.as_deref(),
+            Some("<T:Lfixtures/Shapes;>(TT;)TT;")
+        );
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        for len in 0..SHAPES.len() {
+            assert!(ClassFile::parse(&SHAPES[..len]).is_err(), "{len} bytes");
+        }
+    }
+
+    #[test]
+    fn broken_input_doesnt_panic() {
+        for at in 0..SHAPES.len() {
+            for val in [0x00, 0xff, SHAPES[at] ^ 0x55] {
+                let mut data = SHAPES.to_vec();
+
+                data[at] = val;
+
+                let res = catch_unwind(|| {
+                    if let Ok(class) = ClassFile::parse(&data) {
+                        for it in &class.methods {
+                            let _ = it.types();
+
+                            if let Some(sig) = &it.signature {
+                                pretty_method_signature(&it.name, sig);
+                            }
+                        }
+                    }
+                });
+
+                assert!(res.is_ok(), "{val:#04x} at {at}");
+            }
+        }
+
+        assert!(ClassFile::parse(b"PK\x03\x04").is_err());
+    }
+
+    #[test]
+    fn generic_signatures() {
+        assert_eq!(
+            pretty_method_signature(
+                "largest",
+                "<T:Ljava/lang/Number;>(Ljava/util/List<TT;>;)TT;"
+            ),
+            "<T extends Number> T largest(List<T>)"
+        );
+
+        assert_eq!(
+            pretty_class_signature(
+                "Pair",
+                "<A:Ljava/lang/Object;B::Ljava/lang/Comparable<TB;>;>Ljava/lang/Object;",
+                false
+            ),
+            "Pair<A, B extends Comparable<B>>"
+        );
+
+        assert_eq!(
+            pretty_class_signature(
+                "Entry",
+                "<K:Ljava/lang/Object;>Ljava/lang/Object;Ljava/util/Map$Entry<TK;+Ljava/lang/Number;>;",
+                false
+            ),
+            "Entry<K> implements Map.Entry<K, ? extends Number>"
+        );
+
+        // Type variables can have any name Java allows.
+        assert_eq!(
+            pretty_method_signature("get", "<Ä:Ljava/lang/Object;>()TÄ;"),
+            "<Ä> Ä get()"
+        );
+    }
+}
@@ -0,0 +1,419 @@
+//! Reading `.class` files out of jars, which are zip files.
+
+use anyhow::{Result, bail};
+
+/// Read a little-endian `u16`.
+fn u16_at(data: &[u8], at: usize) -> Result<usize> {
+    match data.get(at..at + 2) {
+        Some(it) => Ok(u16::from_le_bytes([it[0], it[1]]) as usize),
+        None => bail!("Unexpected end of jar!"),
+    }
+}
+
+/// Read a little-endian `u32`.
+fn u32_at(data: &[u8], at: usize) -> Result<usize> {
+    match data.get(at..at + 4) {
+        Some(it) => Ok(u32::from_le_bytes([it[0], it[1], it[2], it[3]]) as usize),
+        None => bail!("Unexpected end of jar!"),
+    }
+}
+
+/// Get the data of every `.class` file in a jar, with its name.
+pub fn jar_classes(data: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    // The end of central directory record is at the end, before a comment.
+    let Some(end) = (0..data.len().saturating_sub(21))
+        .rev()
+        .find(|it| data[*it..].starts_with(&[0x50, 0x4b, 0x05, 0x06]))
+    else {
+        bail!("Not a jar file!");
+    };
+
+    let count = u16_at(data, end + 10)?;
+    let mut at = u32_at(data, end + 16)?;
+    let mut classes = Vec::new();
+
+    for _ in 0..count {
+        if u32_at(data, at)? != 0x02014b50 {
+            bail!("Broken jar central directory!");
+        }
+
+        let method = u16_at(data, at + 10)?;
+        let size = u32_at(data, at + 20)?;
+        let name_len = u16_at(data, at + 28)?;
+        let extra_len = u16_at(data, at + 30)?;
+        let comment_len = u16_at(data, at + 32)?;
+        let offset = u32_at(data, at + 42)?;
+
+        let Some(name) = data.get(at + 46..at + 46 + name_len) else {
+            bail!("Unexpected end of jar!");
+        };
+
+        let name = String::from_utf8_lossy(name).to_string();
+
+        at += 46 + name_len + extra_len + comment_len;
+
+        if !name.ends_with(".class") {
+            continue;
+        }
+
+        if size == 0xffffffff || offset == 0xffffffff {
+            bail!("{name} is in a zip64 jar, which isn't supported!");
+        }
+
+        // The local header has its own name and extra field lengths.
+        let start = offset + 30 + u16_at(data, offset + 26)? + u16_at(data, offset + 28)?;
+
+        let Some(raw) = data.get(start..start + size) else {
+            bail!("Unexpected end of jar!");
+        };
+
+        let class = match method {
+            0 => raw.to_vec(),
+            8 => inflate(raw)?,
+            _ => bail!("{name} uses an unsupported compression method ({method})!"),
+        };
+
+        classes.push((name, class));
+    }
+
+    Ok(classes)
+}
+
+/// Reads bits from deflate data, least significant first.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    bit: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn bits(&mut self, need: u32) -> Result<u32> {
+        while self.count < need {
+            let Some(byte) = self.data.get(self.pos) else {
+                bail!("Unexpected end of compressed data!");
+            };
+
+            self.bit |= (*byte as u32) << self.count;
+            self.pos += 1;
+            self.count += 8;
+        }
+
+        let val = self.bit & ((1u64 << need) - 1) as u32;
+
+        self.bit >>= need;
+        self.count -= need;
+
+        Ok(val)
+    }
+
+    /// Skip to the next byte.
+    fn align(&mut self) {
+        self.bit = 0;
+        self.count = 0;
+    }
+}
+
+/// A canonical Huffman code.
+struct Huffman {
+    /// How many codes there are of each length.
+    counts: [u16; 16],
+
+    /// The symbols, ordered by code.
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; 16];
+
+        for len in lengths {
+            counts[*len as usize] += 1;
+        }
+
+        counts[0] = 0;
+
+        let mut offsets = [0u16; 16];
+
+        for len in 1..15 {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0; lengths.len()];
+
+        for (symbol, len) in lengths.iter().enumerate() {
+            if *len != 0 {
+                symbols[offsets[*len as usize] as usize] = symbol as u16;
+                offsets[*len as usize] += 1;
+            }
+        }
+
+        Self { counts, symbols }
+    }
+
+    fn decode(&self, bits: &mut Bits) -> Result<u16> {
+        let mut code = 0i32;
+        let mut first = 0i32;
+        let mut index = 0i32;
+
+        for len in 1..16 {
+            code |= bits.bits(1)? as i32;
+
+            let count = self.counts[len] as i32;
+
+            if code - count < first {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        bail!("Invalid Huffman code in compressed data!")
+    }
+}
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+
+const DIST_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+
+const DIST_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+
+/// The order code length code lengths come in.
+const CODE_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+/// Decompress raw deflate data.
+pub fn inflate(data: &[u8]) -> Result<Vec<u8>> {
+    let mut bits = Bits {
+        data,
+        pos: 0,
+        bit: 0,
+        count: 0,
+    };
+
+    let mut out = Vec::new();
+
+    loop {
+        let last = bits.bits(1)? == 1;
+
+        match bits.bits(2)? {
+            0 => {
+                bits.align();
+
+                let len = u16_at(data, bits.pos)?;
+
+                let Some(block) = data.get(bits.pos + 4..bits.pos + 4 + len) else {
+                    bail!("Unexpected end of compressed data!");
+                };
+
+                out.extend_from_slice(block);
+                bits.pos += 4 + len;
+            }
+
+            1 => {
+                let mut lengths = [0u8; 288];
+
+                lengths[..144].fill(8);
+                lengths[144..256].fill(9);
+                lengths[256..280].fill(7);
+                lengths[280..].fill(8);
+
+                let lit = Huffman::new(&lengths);
+                let dist = Huffman::new(&[5; 30]);
+
+                inflate_block(&mut bits, &mut out, &lit, &dist)?;
+            }
+
+            2 => {
+                let (lit, dist) = dynamic_codes(&mut bits)?;
+
+                inflate_block(&mut bits, &mut out, &lit, &dist)?;
+            }
+
+            _ => bail!("Invalid block type in compressed data!"),
+        }
+
+        if last {
+            return Ok(out);
+        }
+    }
+}
+
+/// Read the Huffman codes of a dynamic block.
+fn dynamic_codes(bits: &mut Bits) -> Result<(Huffman, Huffman)> {
+    let lit_count = bits.bits(5)? as usize + 257;
+    let dist_count = bits.bits(5)? as usize + 1;
+    let code_count = bits.bits(4)? as usize + 4;
+    let mut code_lengths = [0u8; 19];
+
+    for it in CODE_ORDER.iter().take(code_count) {
+        code_lengths[*it] = bits.bits(3)? as u8;
+    }
+
+    let code = Huffman::new(&code_lengths);
+    let mut lengths = Vec::with_capacity(lit_count + dist_count);
+
+    while lengths.len() < lit_count + dist_count {
+        let (len, times) = match code.decode(bits)? {
+            it @ 0..16 => (it as u8, 1),
+
+            16 => match lengths.last() {
+                Some(last) => (*last, 3 + bits.bits(2)?),
+                None => bail!("Invalid code lengths in compressed data!"),
+            },
+
+            17 => (0, 3 + bits.bits(3)?),
+            _ => (0, 11 + bits.bits(7)?),
+        };
+
+        lengths.extend((0..times).map(|_| len));
+    }
+
+    if lengths.len() > lit_count + dist_count {
+        bail!("Invalid code lengths in compressed data!");
+    }
+
+    Ok((
+        Huffman::new(&lengths[..lit_count]),
+        Huffman::new(&lengths[lit_count..]),
+    ))
+}
+
+/// Decompress a block with Huffman codes.
+fn inflate_block(bits: &mut Bits, out: &mut Vec<u8>, lit: &Huffman, dist: &Huffman) -> Result<()> {
+    loop {
+        let symbol = lit.decode(bits)? as usize;
+
+        if symbol < 256 {
+            out.push(symbol as u8);
+            continue;
+        }
+
+        if symbol == 256 {
+            return Ok(());
+        }
+
+        let symbol = symbol - 257;
+
+        if symbol >= LENGTH_BASE.len() {
+            bail!("Invalid length in compressed data!");
+        }
+
+        let len = LENGTH_BASE[symbol] as usize + bits.bits(LENGTH_EXTRA[symbol] as u32)? as usize;
+        let symbol = dist.decode(bits)? as usize;
+
+        if symbol >= DIST_BASE.len() {
+            bail!("Invalid distance in compressed data!");
+        }
+
+        let back = DIST_BASE[symbol] as usize + bits.bits(DIST_EXTRA[symbol] as u32)? as usize;
+
+        if back > out.len() {
+            bail!("Invalid distance in compressed data!");
+        }
+
+        // The copy can overlap what it's writing.
+        for _ in 0..len {
+            out.push(out[out.len() - back]);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::panic::catch_unwind;
+
+    use super::*;
+
+    const DEFLATED: &[u8] = include_bytes!("../../tests/fixtures/javap/shapes.jar");
+    const STORED: &[u8] = include_bytes!("../../tests/fixtures/javap/shapes-stored.jar");
+    const SHAPES: &[u8] = include_bytes!("../../tests/fixtures/javap/Shapes.class");
+
+    /// `hello` in a stored block.
+    const STORED_BLOCK: &[u8] = &[0x01, 0x05, 0x00, 0xfa, 0xff, 0x68, 0x65, 0x6c, 0x6c, 0x6f];
+
+    /// `hello hello hello` in a fixed block, where the second `hello` is
+    /// copied from the first and the copy overlaps what it's writing.
+    const FIXED_BLOCK: &[u8] = &[0xcb, 0x48, 0xcd, 0xc9, 0xc9, 0x57, 0xc8, 0x40, 0x90, 0x00];
+
+    #[test]
+    fn stored_and_deflated_jars() {
+        let classes = jar_classes(DEFLATED).unwrap();
+        let names = classes
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            names,
+            [
+                "fixtures/Shapes$1.class",
+                "fixtures/Shapes$Circle.class",
+                "fixtures/Shapes$Pair.class",
+                "fixtures/Shapes.class",
+            ]
+        );
+
+        assert_eq!(classes[3].1, SHAPES);
+        assert_eq!(jar_classes(STORED).unwrap(), classes);
+    }
+
+    #[test]
+    fn inflate_blocks() {
+        // The jar's entries are dynamic blocks.
+        assert_eq!(inflate(STORED_BLOCK).unwrap(), b"hello");
+        assert_eq!(inflate(FIXED_BLOCK).unwrap(), b"hello hello hello");
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        for jar in [DEFLATED, STORED] {
+            for len in 0..jar.len() {
+                assert!(jar_classes(&jar[..len]).is_err(), "{len} bytes");
+            }
+        }
+
+        for block in [STORED_BLOCK, FIXED_BLOCK] {
+            for len in 0..block.len() {
+                assert!(inflate(&block[..len]).is_err(), "{len} bytes");
+            }
+        }
+    }
+
+    #[test]
+    fn broken_input_doesnt_panic() {
+        for jar in [DEFLATED, STORED] {
+            for at in 0..jar.len() {
+                for val in [0x00, 0xff, jar[at] ^ 0x55] {
+                    let mut data = jar.to_vec();
+
+                    data[at] = val;
+
+                    assert!(
+                        catch_unwind(|| jar_classes(&data)).is_ok(),
+                        "{val:#04x} at {at}"
+                    );
+                }
+            }
+        }
+
+        assert!(jar_classes(SHAPES).is_err());
+        assert!(inflate(&[0x07]).is_err());
+    }
+}
@@ -0,0 +1,548 @@
+//! Generating extern class declarations from compiled Java classes, with no
+//! JDK needed. This reads `.class` files (directly, in directories, or in
+//! jars) and writes `.rs4j` code, or the Rust code it generates.
+
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use anyhow::{Result, bail};
+use convert_case::{Case, Casing};
+
+use crate::{class::expr::Item, equals_throw, if_else, parser::items};
+
+pub mod class;
+pub mod jar;
+
+use class::{
+    ACC_ABSTRACT, ACC_BRIDGE, ACC_INTERFACE, ACC_PUBLIC, ACC_STATIC, ACC_SYNTHETIC, ClassFile,
+    Descriptor, MethodInfo, pretty_class_signature, pretty_method_signature,
+};
+use jar::jar_classes;
+
+/// Names that can't be used for methods or arguments. Besides Rust keywords,
+/// this has the ones the generated code uses.
+const RESERVED: &[&str] = &[
+    "as", "async", "await", "box", "break", "class", "const", "continue", "crate", "do", "dyn",
+    "else", "enum", "env", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in",
+    "let", "loop", "macro", "match", "method", "mod", "move", "mut", "of", "override", "priv",
+    "pub", "ref", "return", "self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "val", "virtual", "where", "while", "yield",
+];
+
+/// Types `.rs4j` files already have, which classes can't be called.
+const BUILTIN_TYPES: &[&str] = &[
+    "Duration",
+    "Iter",
+    "Object",
+    "PathBuf",
+    "String",
+    "SystemTime",
+];
+
+/// The config for generating extern classes from compiled Java classes.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct JavapConfig {
+    /// The `.class` files, jars, or directories of them to read.
+    pub inputs: Vec<PathBuf>,
+
+    /// The classes to generate, like `java.util.ArrayList` or `java.util.*`.
+    /// If this is empty, every public class is generated.
+    pub classes: Vec<String>,
+
+    /// The file to write. Nothing is written if this isn't set.
+    pub output: Option<PathBuf>,
+
+    /// Generate Rust code instead of `.rs4j` code?
+    pub rust: bool,
+}
+
+impl JavapConfig {
+    /// Create a new [`JavapConfig`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a `.class` file, jar, or directory to read.
+    pub fn input<T>(mut self, val: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.inputs.push(val.into());
+        self
+    }
+
+    /// Add a list of `.class` files, jars, or directories to read.
+    pub fn inputs<T>(mut self, val: Vec<T>) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        for it in val {
+            self.inputs.push(it.into());
+        }
+
+        self
+    }
+
+    /// Select a class to generate, like `java.util.ArrayList`, or every class
+    /// in a package, like `java.util.*`.
+    pub fn class<T>(mut self, val: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        self.classes.push(val.as_ref().to_string());
+        self
+    }
+
+    /// Set the file to write.
+    pub fn output<T>(mut self, val: T) -> Self
+    where
+        T: Into<PathBuf>,
+    {
+        self.output = Some(val.into());
+        self
+    }
+
+    /// Enable/disable generating Rust code instead of `.rs4j` code.
+    pub fn rust(mut self, val: bool) -> Self {
+        self.rust = val;
+        self
+    }
+
+    /// Generate the code, writing it to the output file if there is one.
+    pub fn generate(&self) -> Result<String> {
+        equals_throw!(
+            self.inputs,
+            Vec::<PathBuf>::new(),
+            "Inputs array cannot be empty!"
+        );
+
+        let mut classes = Vec::new();
+
+        for input in &self.inputs {
+            read_input(input, &mut classes)?;
+        }
+
+        classes.retain(|it| self.is_selected(it));
+        classes.sort_by(|a, b| a.name.cmp(&b.name));
+        classes.dedup_by(|a, b| a.name == b.name);
+
+        if classes.is_empty() {
+            bail!("No public classes matched! Check the inputs and class filters.");
+        }
+
+        let code = gen_rs4j_code(&classes)?;
+        let code = if_else!(self.rust, gen_rust_code(&code)?, code);
+
+        if let Some(out) = &self.output {
+            fs::write(out, &code)?;
+        }
+
+        Ok(code)
+    }
+
+    /// Should a class be generated? Anonymous and non-public classes never
+    /// are.
+    fn is_selected(&self, class: &ClassFile) -> bool {
+        let name = class.name.replace("/", ".");
+        let (package, simple) = name.rsplit_once(".").unwrap_or(("", &name));
+
+        let anonymous = simple
+            .split("$")
+            .skip(1)
+            .any(|it| it.starts_with(|it: char| it.is_ascii_digit()));
+
+        if class.access & ACC_PUBLIC == 0 || anonymous || simple.ends_with("-info") {
+            return false;
+        }
+
+        self.classes.is_empty()
+            || self.classes.iter().any(|it| match it.strip_suffix(".*") {
+                Some(pkg) => pkg == package,
+                None => *it == name,
+            })
+    }
+}
+
+/// Read the classes in a `.class` file, jar, or directory.
+pub fn read_input(path: &PathBuf, classes: &mut Vec<ClassFile>) -> Result<()> {
+    if path.is_dir() {
+        let mut entries = fs::read_dir(path)?
+            .map(|it| it.map(|it| it.path()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        entries.sort();
+
+        for entry in entries {
+            if entry.is_dir()
+                || entry
+                    .extension()
+                    .is_some_and(|it| it == "class" || it == "jar")
+            {
+                read_input(&entry, classes)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    let data = fs::read(path)?;
+
+    if path.extension().is_some_and(|it| it == "jar") {
+        for (name, data) in jar_classes(&data)? {
+            match ClassFile::parse(&data) {
+                Ok(it) => classes.push(it),
+                Err(err) => bail!("Failed to read {name} in {}: {err}", path.display()),
+            }
+        }
+    } else {
+        match ClassFile::parse(&data) {
+            Ok(it) => classes.push(it),
+            Err(err) => bail!("Failed to read {}: {err}", path.display()),
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the Rust name of a generated class.
+fn class_rust_name(name: &str) -> String {
+    name.rsplit("/").next().unwrap_or(name).replace("$", "")
+}
+
+/// Make a name usable in Rust, adding a `_` to reserved ones.
+fn rust_ident(name: &str) -> String {
+    if_else!(RESERVED.contains(&name), format!("{name}_"), name.into())
+}
+
+/// Is this a name `.rs4j` files can use?
+fn is_ident(name: &str) -> bool {
+    name.starts_with(|it: char| it.is_ascii_alphabetic() || it == '_')
+        && name
+            .chars()
+            .all(|it| it.is_ascii_alphanumeric() || it == '_')
+}
+
+/// Get the `.rs4j` type of a descriptor. Classes that aren't being generated
+/// (other than `String` and `Object`) and arrays can't be used, so this
+/// gives the reason instead.
+fn rs4j_type(desc: &Descriptor, names: &HashMap<&str, String>) -> Result<String, String> {
+    Ok(match desc {
+        Descriptor::Primitive('B') => "i8".into(),
+        Descriptor::Primitive('S') => "i16".into(),
+        Descriptor::Primitive('I') => "i32".into(),
+        Descriptor::Primitive('J') => "i64".into(),
+        Descriptor::Primitive('F') => "f32".into(),
+        Descriptor::Primitive('D') => "f64".into(),
+        Descriptor::Primitive('Z') => "bool".into(),
+        Descriptor::Primitive('C') => "char".into(),
+        Descriptor::Primitive(_) => "()".into(),
+        Descriptor::Class(it) if it == "java/lang/String" => "String".into(),
+        Descriptor::Class(it) if it == "java/lang/Object" => "Object".into(),
+
+        Descriptor::Class(it) => match names.get(it.as_str()) {
+            Some(it) => it.clone(),
+            None => {
+                return Err(format!(
+                    "uses {}, which isn't generated",
+                    it.replace("/", ".")
+                ));
+            }
+        },
+
+        Descriptor::Array(_) => return Err("uses an array".into()),
+    })
+}
+
+/// A method of a class, ready to be written.
+struct JavapMethod<'a> {
+    info: &'a MethodInfo,
+    args: Vec<(String, String)>,
+    ret: String,
+}
+
+impl JavapMethod<'_> {
+    /// The suffix for telling this overload apart, from its argument types.
+    fn suffix(&self) -> String {
+        self.args
+            .iter()
+            .map(|(_, ty)| {
+                if_else!(
+                    ty.starts_with(char::is_uppercase),
+                    format!("_{}", ty.to_case(Case::Snake)),
+                    format!("_{ty}")
+                )
+            })
+            .collect()
+    }
+}
+
+/// Generate `.rs4j` code for classes. Methods that can't be declared are
+/// left as comments saying why.
+pub fn gen_rs4j_code(classes: &[ClassFile]) -> Result<String> {
+    let mut names = HashMap::<&str, String>::new();
+
+    for class in classes {
+        let mut name = class_rust_name(&class.name);
+
+        // Classes with the same name in different packages get the package's
+        // name in front, and ones named like a built-in type do too.
+        if names.values().any(|it| *it == name) || BUILTIN_TYPES.contains(&name.as_str()) {
+            let package = class.name.rsplit("/").nth(1).unwrap_or_default();
+
+            name = format!("{}{name}", package.to_case(Case::Pascal));
+        }
+
+        if let Some((other, _)) = names.iter().find(|(_, it)| **it == name) {
+            bail!(
+                "Classes {} and {} would both be called {name}! Select only one of them.",
+                other.replace("/", "."),
+                class.name.replace("/", ".")
+            );
+        }
+
+        names.insert(class.name.as_str(), name);
+    }
+
+    let mut data = "// Generated by `rs4j javap`.\n".to_string();
+
+    for class in classes {
+        data.push_str(&format!("\n{}", gen_class(class, &names)?));
+    }
+
+    Ok(data)
+}
+
+/// Generate the `.rs4j` code for one class.
+fn gen_class(class: &ClassFile, names: &HashMap<&str, String>) -> Result<String> {
+    let java_name = class.name.replace("/", ".");
+    let simple = java_name.rsplit(".").next().unwrap_or(&java_name);
+    let abstract_ = class.access & (ACC_ABSTRACT | ACC_INTERFACE) != 0;
+    let mut methods = Vec::new();
+    let mut skipped = Vec::new();
+
+    for info in &class.methods {
+        let init = info.name == "<init>";
+
+        if info.access & ACC_PUBLIC == 0
+            || info.access & (ACC_SYNTHETIC | ACC_BRIDGE) != 0
+            || info.name == "<clinit>"
+            || (init && abstract_)
+        {
+            continue;
+        }
+
+        if !init && !is_ident(&info.name) {
+            skipped.push(format!("{}: its name can't be used in Rust", info.name));
+            continue;
+        }
+
+        let (params, ret) = info.types()?;
+        let mut args = Vec::new();
+        let mut error = None;
+
+        for (i, param) in params.iter().enumerate() {
+            let name = info
+                .params
+                .get(i)
+                .filter(|it| is_ident(it))
+                .map(|it| rust_ident(&it.to_case(Case::Snake)))
+                .filter(|it| args.iter().all(|(other, _)| other != it))
+                .unwrap_or_else(|| format!("arg{i}"));
+
+            match rs4j_type(param, names) {
+                Ok(ty) => args.push((name, ty)),
+                Err(err) => error = error.or(Some(err)),
+            }
+        }
+
+        let ret = match rs4j_type(&ret, names) {
+            Ok(ret) => ret,
+            Err(err) => error.get_or_insert(err).clone(),
+        };
+
+        if let Some(err) = error {
+            skipped.push(format!("{}{}: {err}", info.name, info.descriptor));
+            continue;
+        }
+
+        methods.push(JavapMethod { info, args, ret });
+    }
+
+    // The overload with the fewest arguments keeps the plain name.
+    methods.sort_by_key(|it| {
+        (
+            it.info.name != "<init>",
+            it.info.name.clone(),
+            it.args.len(),
+        )
+    });
+
+    let mut used = Vec::<String>::new();
+    let mut lines = Vec::new();
+
+    for method in &methods {
+        let info = method.info;
+        let init = info.name == "<init>";
+        let base = if_else!(
+            init,
+            "new".into(),
+            rust_ident(&info.name.to_case(Case::Snake))
+        );
+        let mut name = base.clone();
+
+        if used.contains(&name) {
+            name = format!("{base}{}", method.suffix());
+        }
+
+        let mut i = 2;
+
+        while used.contains(&name) {
+            name = format!("{base}{}_{i}", method.suffix());
+            i += 1;
+        }
+
+        used.push(name.clone());
+
+        let mut out = String::new();
+
+        if let Some(sig) = &info.signature {
+            out.push_str(&format!(
+                "    // {}\n",
+                pretty_method_signature(if_else!(init, simple, &info.name), sig)
+            ));
+        }
+
+        if !init && name.to_case(Case::Camel) != info.name {
+            out.push_str(&format!("    #[java_name = \"{}\"]\n", info.name));
+        }
+
+        let modifier = match () {
+            _ if init => "init ",
+            _ if info.access & ACC_STATIC != 0 => "static ",
+            _ => "",
+        };
+
+        let args = method
+            .args
+            .iter()
+            .map(|(name, ty)| format!("{name}: {ty}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let ret = if_else!(
+            init || method.ret == "()",
+            String::new(),
+            format!(" -> {}", method.ret)
+        );
+
+        out.push_str(&format!("    {modifier}fn {name}({args}){ret};"));
+        lines.push(out);
+    }
+
+    for it in skipped {
+        lines.push(format!("    // Skipped {it}"));
+    }
+
+    let mut data = String::new();
+    let rust_name = &names[class.name.as_str()];
+
+    let rename = if_else!(
+        *rust_name == class_rust_name(&class.name),
+        String::new(),
+        format!(" = {rust_name}")
+    );
+
+    if let Some(sig) = &class.signature {
+        data.push_str(&format!(
+            "// {}\n",
+            pretty_class_signature(simple, sig, class.access & ACC_INTERFACE != 0)
+        ));
+    }
+
+    data.push_str(&format!(
+        "extern class {java_name}{rename} {{\n{}\n}};\n",
+        lines.join("\n\n")
+    ));
+
+    Ok(data)
+}
+
+/// Generate the Rust code for `.rs4j` code with only extern classes.
+pub fn gen_rust_code(code: &str) -> Result<String> {
+    let mut externs = Vec::new();
+
+    for item in items(code)? {
+        match item {
+            Item::Extern(it) => externs.push(it),
+            _ => bail!("Only extern classes can be generated here!"),
+        }
+    }
+
+    let mut data = "use rs4j::prelude::*;\n\n".to_string();
+
+    for class in &externs {
+        data.push_str(&format!("{}\n\n", class.rust_code(&externs)?));
+    }
+
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/javap")
+            .join(name)
+    }
+
+    #[test]
+    fn generate_from_jar() {
+        let code = JavapConfig::new()
+            .input(fixture("shapes.jar"))
+            .class("fixtures.*")
+            .generate()
+            .unwrap();
+
+        assert_eq!(code, fs::read_to_string(fixture("shapes.rs4j")).unwrap());
+
+        let stored = JavapConfig::new()
+            .input(fixture("shapes-stored.jar"))
+            .generate()
+            .unwrap();
+
+        assert_eq!(stored, code);
+    }
+
+    #[test]
+    fn generate_from_class() {
+        let code = JavapConfig::new()
+            .input(fixture("Shapes.class"))
+            .generate()
+            .unwrap();
+
+        assert!(code.contains("extern class fixtures.Shapes {"));
+        assert!(!code.contains("Shapes$Circle"));
+    }
+
+    #[test]
+    fn generate_rust() {
+        let code = JavapConfig::new()
+            .input(fixture("shapes.jar"))
+            .rust(true)
+            .generate()
+            .unwrap();
+
+        assert!(code.starts_with("use rs4j::prelude::*;"));
+    }
+
+    #[test]
+    fn unmatched_classes_are_an_error() {
+        let res = JavapConfig::new()
+            .input(fixture("shapes.jar"))
+            .class("java.util.*")
+            .generate();
+
+        assert!(res.is_err());
+    }
+}
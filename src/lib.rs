@@ -7,6 +7,9 @@ extern crate peg;
 
 pub extern crate anyhow;
 
+#[cfg(feature = "tracing")]
+pub extern crate tracing;
+
 #[cfg(feature = "build")]
 pub mod build;
 
@@ -14,18 +17,41 @@ pub mod class;
 pub mod codegen;
 pub mod internal;
 pub mod java;
+
+#[cfg(feature = "build")]
+pub mod javap;
+
 pub mod loader;
 pub mod macros;
 pub mod parser;
 
+pub use internal::vm::{on_load, on_unload};
+
 pub mod prelude {
     //! Base types.
 
+    pub use super::internal::array::*;
     pub use super::internal::base::*;
+    pub use super::internal::cache::*;
+    pub use super::internal::call::*;
     pub use super::internal::conv::*;
+    pub use super::internal::guard::*;
     pub use super::internal::include::*;
+    pub use super::internal::iter::*;
+    pub use super::internal::leaks::*;
+    #[cfg(feature = "log")]
+    pub use super::internal::logging::*;
+    pub use super::internal::path::*;
+    pub use super::internal::stats::*;
+    pub use super::internal::task::*;
+    pub use super::internal::time::*;
     pub use super::internal::types::*;
-    pub use jni::objects::{JClass, JObject, JString};
+    pub use super::internal::vm::*;
+    pub use jni::objects::{
+        GlobalRef, JClass, JMethodID, JObject, JObjectArray, JPrimitiveArray, JStaticMethodID,
+        JString, JValue, JValueOwned,
+    };
+    pub use jni::signature::{Primitive, ReturnType};
     pub use jni::sys::{
         jarray, jboolean, jbyte, jchar, jclass, jdouble, jfloat, jint, jlong, jobject, jshort,
         jstring, jvalue,
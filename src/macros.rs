@@ -22,6 +22,32 @@ macro_rules! if_else {
     }
 }
 
+/// Define `JNI_OnLoad` and `JNI_OnUnload`, calling [`on_load`](crate::on_load)
+/// and [`on_unload`](crate::on_unload). Libraries with their own hooks should
+/// call those from them instead.
+#[macro_export]
+macro_rules! jni_onload {
+    () => {
+        #[unsafe(no_mangle)]
+        #[allow(non_snake_case)]
+        pub unsafe extern "system" fn JNI_OnLoad(
+            vm: *mut $crate::prelude::sys::JavaVM,
+            _reserved: *mut ::std::ffi::c_void,
+        ) -> $crate::prelude::jint {
+            unsafe { $crate::on_load(vm) }
+        }
+
+        #[unsafe(no_mangle)]
+        #[allow(non_snake_case)]
+        pub unsafe extern "system" fn JNI_OnUnload(
+            _vm: *mut $crate::prelude::sys::JavaVM,
+            _reserved: *mut ::std::ffi::c_void,
+        ) {
+            $crate::on_unload();
+        }
+    };
+}
+
 /// Make a getter
 #[macro_export]
 macro_rules! getter {
@@ -1,6 +1,6 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
-use rs4j::build::BindgenConfig;
+use rs4j::{build::BindgenConfig, javap::JavapConfig};
 use serde_json::Value;
 use std::{env, fs, path::PathBuf, process::Command};
 
@@ -35,6 +35,19 @@ pub enum Commands {
         #[arg(short, long)]
         zigbuild: bool,
     },
+
+    Javap {
+        inputs: Vec<PathBuf>,
+
+        #[arg(short, long)]
+        class: Vec<String>,
+
+        #[arg(short, long)]
+        out: Option<PathBuf>,
+
+        #[arg(short, long)]
+        rust: bool,
+    },
 }
 
 pub fn run_build(args: Vec<String>, zigbuild: bool) -> Result<()> {
@@ -139,6 +152,25 @@ pub fn main() -> Result<()> {
         Commands::Build { args, zigbuild } => {
             run_build(args, zigbuild)?;
         }
+
+        Commands::Javap {
+            inputs,
+            class,
+            out,
+            rust,
+        } => {
+            let mut config = JavapConfig::new().inputs(inputs).rust(rust);
+
+            for it in class {
+                config = config.class(it);
+            }
+
+            if let Some(out) = out {
+                config.output(out).generate()?;
+            } else {
+                print!("{}", config.generate()?);
+            }
+        }
     }
 
     Ok(())
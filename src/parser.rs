@@ -3,10 +3,18 @@
 use crate::class::{
     Class,
     arg::FunctionArg,
-    expr::Expr,
-    field::Field,
-    generic::TypeGeneric,
+    constant::{Constant, Literal},
+    derive::Derive,
+    expr::{Expr, Item},
+    external::ExternClass,
+    field::{Access, Field},
+    generic::{MethodGeneric, TypeGeneric},
+    imp::TraitImpl,
+    inherit::{Inherit, Via},
     method::Method,
+    property::Property,
+    static_field::StaticField,
+    threading::Threading,
     ty::{Type, TypeKind},
 };
 
@@ -17,21 +25,93 @@ parser! {
         pub rule classes() -> Vec<Class>
             = c: (class()*) { c }
 
+        /// Parse many [`Item`]s, with comments between them.
+        pub rule items() -> Vec<Item>
+            = i: (_item_or_comment()*) { i.into_iter().flatten().collect() }
+
+        rule _item_or_comment() -> Option<Item>
+            = __ _ comment() _ "\n" { None }
+            / i: item() { Some(i) }
+
+        /// Parse an [`Item`].
+        pub rule item() -> Item
+            = e: extern_class() { Item::Extern(e) }
+            / c: class() { Item::Class(c) }
+            / i: trait_impl() { Item::Impl(i) }
+
+        /// Parse an [`ExternClass`].
+        pub rule extern_class() -> ExternClass
+            = _ e: _extern_class() _ "\n" { e }
+
+        rule _extern_class() -> ExternClass
+            = __ _ "extern" _ "class" _ path: (_java_ident() ++ ".") _
+            rust_name: ("=" _ id: _ident() _ { id })? _
+            stmts: ("{" _ s: stmts() _ "}" _ ";"? { s } / ";" { Vec::new() })
+
+            {?
+                if stmts.iter().any(|it| !it.is_method() && !it.is_none()) {
+                    return Err("only methods in an extern class");
+                }
+
+                let methods = stmts.iter().filter_map(|it| it.get_method()).collect::<Vec<_>>();
+
+                for method in &methods {
+                    ExternClass::check_method(method)?;
+                }
+
+                let (name, package) = path.split_last().unwrap();
+
+                Ok(ExternClass {
+                    name: name.clone(),
+                    package: package.join("."),
+                    rust_name,
+                    methods,
+                })
+            }
+
+        /// Parse a [`TraitImpl`].
+        pub rule trait_impl() -> TraitImpl
+            = i: _trait_impl() _ "\n" { i }
+
+        rule _trait_impl() -> TraitImpl
+            = __ _ "impl" _ tr: _type() _ "for" _ target: _ident() _ _generics()? _
+            output: ("-" _ ">" _ ty: _type() _ { ty })? _
+            methods: (";" { None } / "{" _ stmts: stmts() _ "}" _ ";"? { Some(stmts) })
+
+            {
+                TraitImpl {
+                    tr,
+                    target,
+                    output,
+                    methods: methods.map(|it| it.iter().filter_map(|v| v.get_method()).collect()),
+                }
+            }
+
+        /// Parse a [`TraitImpl`] as an [`Expr`].
+        pub rule impl_block() -> Expr = val: _trait_impl() { Expr::Impl(val) }
+
         /// Parse a [`Class`].
         pub rule class() -> Class
             = _ e: _class() _ "\n" { e }
 
             rule _class() -> Class
-            = __ _ wrapped: "wrapped"? _
-            "class" _ name: _ident() _
+            = __ _ threading: (t: _threading() _ { t })? _
+            wrapped: "wrapped"? _
+            value: _class_k() _ name: _ident() _
             generics: _generics()? _
             real_name: ("=" _ id: _ident() _ generics: _generics()? { (id, generics.unwrap_or_default()) })? _
-            "{" _ stmts: stmts() _ "}" _ ";"?
+            inherits: (":" _ i: _inherit() { i })? _
+            derives: ((":" / "+") _ d: (_derive() ++ (_ "+" _)) { d })? _
+            stmts: ("{" _ s: stmts() _ "}" _ ";"? { s } / ";" { Vec::new() })
             {
                 let class_generics: Vec<TypeGeneric> = generics.unwrap_or_default().iter().map(|v| v.clone().into()).collect::<Vec<_>>();
                 let fields = stmts.iter().filter_map(|v| v.get_field()).collect::<Vec<_>>();
+                let constants = stmts.iter().filter_map(|v| v.get_const()).collect::<Vec<_>>();
+                let statics = stmts.iter().filter_map(|v| v.get_static_field()).collect::<Vec<_>>();
+                let properties = stmts.iter().filter_map(|v| v.get_property()).collect::<Vec<_>>();
                 let mut generics = stmts.iter().filter_map(|v| v.get_generic()).collect::<Vec<_>>();
                 let methods = stmts.iter().filter_map(|v| v.get_method()).collect::<Vec<_>>();
+                let impls = stmts.iter().filter_map(|v| v.get_impl()).collect::<Vec<_>>();
 
                 for item in class_generics {
                     if generics.iter().find(|v| v.name == item.name).is_none() {
@@ -43,14 +123,44 @@ parser! {
                     name,
                     real_name,
                     wrapped: wrapped.is_some(),
+                    value,
+                    threading: threading.unwrap_or_default(),
+                    derives: derives.unwrap_or_default(),
+                    impls,
+                    inherits,
                     package: String::new(),
                     fields,
+                    constants,
+                    statics,
+                    properties,
                     imports: Class::default_imports(),
                     generics,
                     methods,
                 }
             }
 
+        /// Parse the class keyword, returning whether it's a value class.
+        rule _class_k() -> bool
+            = "value" _ "class" { true }
+            / "record" !_ident_char() { true }
+            / "class" { false }
+
+        /// Parse a [`Threading`] modifier.
+        rule _threading() -> Threading
+            = name: $("sync" / "send" / "local") !_ident_char() {? Threading::from_name(name).ok_or("a threading modifier") }
+
+        /// Parse a [`Derive`] marker.
+        rule _derive() -> Derive
+            = name: _ident() {? Derive::from_name(&name).ok_or("a derivable trait") }
+
+        /// Parse a base class to [`Inherit`].
+        rule _inherit() -> Inherit
+            = base: _ident() _ "via" _ via: _via() { Inherit { base, via } }
+
+        rule _via() -> Via
+            = "Deref" !_ident_char() { Via::Deref }
+            / "AsRef" !_ident_char() { Via::AsRef }
+
         /// Parse multiple [`Expr`]s.
         pub rule stmts() -> Vec<Expr>
             = s: (stmt()*) { s }
@@ -60,7 +170,7 @@ parser! {
             = _ e: expr() _ "\n" { e }
 
         /// Parse an [`Expr`].
-        pub rule expr() -> Expr = method() / generic() / field() / comment() / { Expr::None }
+        pub rule expr() -> Expr = method() / generic() / constant() / static_field() / property() / field() / impl_block() / comment() / { Expr::None }
 
         /// Parse a [`Method`] as an [`Expr`].
         pub rule generic() -> Expr = val: _generic() { Expr::Generic(val) }
@@ -74,43 +184,147 @@ parser! {
         pub rule field() -> Expr = val: _field() { Expr::Field(val) }
 
         rule _field() -> Field
-            = __ _ rust: "rust"? _ "field" _ name: _ident() _ ":"
+            = __ _ rust: "rust"? _ access: _access()? _ "field" _ name: _ident() _ ":"
             _ ty: _type() _ ";"
-            { Field { name, rust: rust.is_some(), ty } }
+            { Field { name, rust: rust.is_some(), ty, access: access.unwrap_or_default() } }
+
+        /// Parse a field's [`Access`].
+        rule _access() -> Access
+            = "readonly" { Access::ReadOnly }
+            / "writeonly" { Access::WriteOnly }
+
+        /// Parse a [`Property`] as an [`Expr`].
+        pub rule property() -> Expr = val: _property() { Expr::Property(val) }
+
+        rule _property() -> Property
+            = __ _ "property" _ name: _ident() _ ":" _ ty: _type() _ "=" _ getter: _ident() _
+            setter: ("/" _ s: _ident() { s })? _ ";"
+            { Property { name, ty, getter, setter } }
+
+        /// Parse a [`Constant`] as an [`Expr`].
+        pub rule constant() -> Expr = val: _constant() { Expr::Const(val) }
+
+        rule _constant() -> Constant
+            = __ _ "const" _ name: _ident() _ ":" _ ty: _type() _ "=" _ value: _literal() _ ";"
+            {? Constant::new(name, ty, value) }
+
+        /// Parse a [`StaticField`] as an [`Expr`].
+        pub rule static_field() -> Expr = val: _static_field() { Expr::StaticField(val) }
+
+        rule _static_field() -> StaticField
+            = __ _ "static" _ "field" _ name: _ident() _ ":" _ ty: _type() _ ";"
+            { StaticField { name, ty } }
+
+        /// Parse a [`Literal`].
+        rule _literal() -> Literal
+            = "true" !_ident_char() { Literal::Bool(true) }
+            / "false" !_ident_char() { Literal::Bool(false) }
+            / s: $("\"" ("\\" [_] / [^ '"' | '\\'])* "\"") {? Literal::unescape(&s[1..s.len() - 1]).map(Literal::Str) }
+            / c: $("'" ("\\" [_] / [^ '\'' | '\\'])+ "'") {?
+                let val = Literal::unescape(&c[1..c.len() - 1])?;
+                let mut chars = val.chars();
+
+                match (chars.next(), chars.next()) {
+                    (Some(it), None) => Ok(Literal::Char(it)),
+                    _ => Err("a single character"),
+                }
+            }
+            / n: $("-"? ['0'..='9'] ['0'..='9' | '_']* "." ['0'..='9'] ['0'..='9' | '_']*) { Literal::Float(n.replace('_', "")) }
+            / n: $("-"? ['0'..='9'] ['0'..='9' | '_']*) {? n.replace('_', "").parse().map(Literal::Int).or(Err("an integer")) }
 
         /// Parse a [`FunctionArg`].
         pub rule fn_arg() -> FunctionArg
             = _ name: _ident() _ ":" _
             into: "#into"? _
+            varargs: "..."? _
             borrow: "&"? _
             mutable: "mut"? _
-            ty: _type() _
+            ty: (_slice() / _type()) _
+            default: ("=" _ v: _literal() _ { v })?
 
-            {
-                FunctionArg {
+            {?
+                if default.as_ref().is_some_and(|it| !it.fits(&ty.kind)) {
+                    return Err("a default value matching the argument's type");
+                }
+
+                if let TypeKind::Slice(item) = &ty.kind {
+                    if varargs.is_some() || borrow.is_none() || mutable.is_some() {
+                        return Err("a borrowed slice, like `&[T]`");
+                    }
+
+                    if matches!(item.kind, TypeKind::Void | TypeKind::Iter | TypeKind::Slice(_)) {
+                        return Err("a slice of values");
+                    }
+                }
+
+                let ty = match varargs {
+                    Some(_) if borrow.is_some() || mutable.is_some() => {
+                        return Err("varargs of owned values, like `...T`");
+                    }
+
+                    Some(_) if matches!(ty.kind, TypeKind::Void | TypeKind::Iter) => {
+                        return Err("varargs of values");
+                    }
+
+                    Some(_) => Type::new(TypeKind::Slice(Box::new(ty)), None),
+                    None => ty,
+                };
+
+                Ok(FunctionArg {
                     name,
                     ty,
                     borrow: borrow.is_some(),
                     mutable: mutable.is_some(),
                     into: into.is_some(),
-                }
+                    default,
+                    varargs: varargs.is_some(),
+                })
             }
 
+        /// Parse a slice, like `[T]`.
+        rule _slice() -> Type
+            = "[" _ item: _type() _ "]" { Type::new(TypeKind::Slice(Box::new(item)), None) }
+
         /// Parse a [`Method`] as an [`Expr`].
         pub rule method() -> Expr = val: _method() { Expr::Method(val) }
 
         rule _method() -> Method
-            = __ _ rust_name: ("[" _ id: _ident() _ "]" { id })?
+            = __ _ attrs: (a: _attr() __ { a })* _ rust_name: ("[" _ id: _ident() _ "]" { id })?
             _ modifiers: (v: _func_modifier() ** _ { v }) _
             "fn" _ object: (id: _ident() _ "::" _ { id })? _
             name: _ident() _
+            generics: ("<" _ g: (_method_generic() ++ (_ "," _)) _ ">" { g })? _
             "(" _ args: (fn_arg() ** ",") _ ")" _
             ret: ("-" _ ">" _ ty: _type() _ { ty })? _
             ";"
 
-            {
-                Method {
+            {?
+                if args.iter().skip_while(|it| it.default.is_none()).any(|it| it.default.is_none()) {
+                    return Err("default values only on the last arguments");
+                }
+
+                if args.iter().rev().skip(1).any(|it| it.varargs) {
+                    return Err("varargs only on the last argument");
+                }
+
+                let java_name = attrs.iter().find(|(k, _)| k == "java_name").map(|(_, v)| v.clone());
+
+                if java_name.is_some() && modifiers.contains(&"init".into()) {
+                    return Err("a method, since constructors keep the class name in Java");
+                }
+
+                if modifiers.contains(&"async".into()) && modifiers.contains(&"mut".into()) {
+                    return Err("a non-`mut` method, since `async` methods work on a copy of the object");
+                }
+
+                Ok(Method {
                     custom_name: rust_name,
+                    java_name,
+                    operator: None,
+                    interface: None,
+                    inherited: None,
+                    generics: generics.unwrap_or_default(),
+                    instance: Vec::new(),
                     args,
                     object,
                     name,
@@ -121,6 +335,30 @@ parser! {
                     is_mut: modifiers.contains(&"mut".into()),
                     is_optional: modifiers.contains(&"optional".into()),
                     is_static: modifiers.contains(&"static".into()),
+                    is_async: modifiers.contains(&"async".into()),
+                })
+            }
+
+        /// Parse a method's type parameter, like `T in [i32, String]` or `T: Shape`.
+        rule _method_generic() -> MethodGeneric
+            = name: _ident() _ bounds: (":" _ b: (_type() ++ (_ "+" _)) { b })? _
+            types: ("in" _ "[" _ t: (_type() ++ (_ "," _)) _ "]" { t })?
+            {
+                MethodGeneric {
+                    name,
+                    bounds: bounds.unwrap_or_default(),
+                    erased: types.is_none(),
+                    types: types.unwrap_or_default(),
+                }
+            }
+
+        /// Parse an attribute, like `#[java_name = "of"]`.
+        rule _attr() -> (String, String)
+            = "#" _ "[" _ key: _ident() _ "=" _ "\"" value: $([^ '"']*) "\"" _ "]"
+            {?
+                match key.as_str() {
+                    "java_name" => Ok((key, value.into())),
+                    _ => Err("a known attribute"),
                 }
             }
 
@@ -144,15 +382,21 @@ parser! {
         rule _i64_k() -> TypeKind = "i64" { TypeKind::I64 }
         rule _f32_k() -> TypeKind = "f32" { TypeKind::F32 }
         rule _f64_k() -> TypeKind = "f64" { TypeKind::F64 }
-        rule _bool_k() -> TypeKind = "bool" { TypeKind::Bool }
-        rule _char_k() -> TypeKind = "char" { TypeKind::Char }
-        rule _str_k() -> TypeKind = "String" { TypeKind::String }
+        rule _bool_k() -> TypeKind = "bool" !_ident_char() { TypeKind::Bool }
+        rule _char_k() -> TypeKind = "char" !_ident_char() { TypeKind::Char }
+        rule _str_k() -> TypeKind = "String" !_ident_char() { TypeKind::String }
         rule _void_k() -> TypeKind = "()" { TypeKind::Void }
+        rule _duration_k() -> TypeKind = "Duration" !_ident_char() { TypeKind::Duration }
+        rule _system_time_k() -> TypeKind = "SystemTime" !_ident_char() { TypeKind::SystemTime }
+        rule _path_buf_k() -> TypeKind = "PathBuf" !_ident_char() { TypeKind::PathBuf }
+        rule _iter_k() -> TypeKind = "Iter" !_ident_char() { TypeKind::Iter }
+        rule _tuple_k() -> TypeKind = "(" _ items: ((_ t: _type() _ { t }) **<2,12> ",") _ ")" { TypeKind::Tuple(items) }
         rule _other_k() -> TypeKind = id: _ident() { TypeKind::Other(id) }
         rule _uint_k() -> TypeKind = _u8_k() / _u16_k() / _u32_k() / _u64_k()
         rule _int_k() -> TypeKind = _i8_k() / _i16_k() / _i32_k() / _i64_k()
         rule _float_k() -> TypeKind = _f32_k() / _f64_k()
-        rule _extra_k() -> TypeKind = _bool_k() / _char_k() / _str_k() / _void_k() / _other_k()
+        rule _std_k() -> TypeKind = _duration_k() / _system_time_k() / _path_buf_k() / _iter_k()
+        rule _extra_k() -> TypeKind = _bool_k() / _char_k() / _str_k() / _void_k() / _tuple_k() / _std_k() / _other_k()
 
         // Generics
 
@@ -166,8 +410,9 @@ parser! {
         rule _mut() -> String = "mut" { "mut".into() }
         rule _consumed() -> String = "consumed" { "consumed".into() }
         rule _optional() -> String = "optional" { "optional".into() }
+        rule _async() -> String = "async" { "async".into() }
 
-        rule _func_modifier() -> String = _boxed() / _static() / _init() / _mut() / _consumed() / _optional()
+        rule _func_modifier() -> String = _boxed() / _static() / _init() / _mut() / _consumed() / _optional() / _async()
 
         // Utilities
 
@@ -177,6 +422,15 @@ parser! {
                 { n.to_owned() }
             } / expected!("identifier")
 
+        /// Parse a Java identifier, which can have `$`s, like nested classes.
+        rule _java_ident() -> String
+            = quiet! {
+                n: $(['a'..='z' | 'A'..='Z' | '_' | '$']['a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '$']*)
+                { n.to_owned() }
+            } / expected!("identifier")
+
+        rule _ident_char() = ['a'..='z' | 'A'..='Z' | '0'..='9' | '_']
+
         rule comment() -> Expr = "//" _ [^ '\n']* { Expr::None }
         rule _() = quiet! { [' ' | '\t']* }
         rule __() = quiet! { [' ' | '\t' | '\n']* }
@@ -184,3 +438,72 @@ parser! {
 }
 
 pub use rs4j_parser::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::cx::Generator;
+
+    #[test]
+    fn mut_async_methods_are_rejected() {
+        let err = class("class Thing {\n    mut async fn bump();\n};\n").unwrap_err();
+
+        assert!(err.expected.to_string().contains("`async` methods"));
+        assert!(class("class Thing {\n    async fn bump();\n};\n").is_ok());
+    }
+
+    /// Parse a constant of type `ty`, and get its value in Java and Kotlin.
+    fn constant_value(ty: &str, value: &str) -> Option<(String, String)> {
+        let class = class(&format!(
+            "class Thing {{\n    const A: {ty} = {value};\n}};\n"
+        ))
+        .ok()?;
+        let constant = &class.constants[0];
+
+        let java = Generator::default();
+        let kotlin = Generator {
+            kotlin: true,
+            ..Default::default()
+        };
+
+        Some((
+            constant.value.java_value(&constant.ty.kind, &java),
+            constant.value.java_value(&constant.ty.kind, &kotlin),
+        ))
+    }
+
+    #[test]
+    fn literal_escapes_are_translated() {
+        let (java, kotlin) =
+            constant_value("String", r#""a\tb\x7f \u{e9}\u{1F600} \"$\\""#).unwrap();
+
+        assert_eq!(java, r#""a\tb\u007f \u00e9\ud83d\ude00 \"$\\""#);
+        assert_eq!(kotlin, r#""a\tb\u007f \u00e9\ud83d\ude00 \"\$\\""#);
+
+        let (java, _) = constant_value("char", r"'\u{20AC}'").unwrap();
+
+        assert_eq!(java, r"'\u20ac'");
+        assert_eq!(constant_value("char", r"'\''").unwrap().0, r"'\''");
+    }
+
+    #[test]
+    fn invalid_literals_are_rejected() {
+        assert!(constant_value("String", r#""\x80""#).is_none());
+        assert!(constant_value("String", r#""\u{D800}""#).is_none());
+        assert!(constant_value("String", r#""\q""#).is_none());
+        assert!(constant_value("char", r"'\u{1F600}'").is_none());
+        assert!(constant_value("char", "'ab'").is_none());
+    }
+
+    #[test]
+    fn unsigned_constants_fit_java() {
+        assert_eq!(constant_value("u32", "2147483647").unwrap().0, "2147483647");
+        assert_eq!(
+            constant_value("u64", "9223372036854775807").unwrap().0,
+            "9223372036854775807L"
+        );
+        assert!(constant_value("u32", "2147483648").is_none());
+        assert!(constant_value("u64", "18446744073709551615").is_none());
+        assert!(constant_value("u8", "200").is_none());
+    }
+}
@@ -0,0 +1,54 @@
+package fixtures;
+
+import java.util.List;
+
+/**
+ * The classes in the javap test fixtures.
+ */
+public class Shapes {
+    public Shapes() {}
+
+    public Shapes(int sides) {}
+
+    public int area(int side) {
+        return side * side;
+    }
+
+    public double area(double width, double height) {
+        return width * height;
+    }
+
+    public static <T extends Number> T largest(List<T> values) {
+        return values.get(0);
+    }
+
+    public <T extends Shapes> T pick(T shape) {
+        return shape;
+    }
+
+    public int[] corners() {
+        return new int[0];
+    }
+
+    public Runnable printer() {
+        return new Runnable() {
+            public void run() {}
+        };
+    }
+
+    public static class Circle {
+        public Circle(double radius) {}
+
+        public double radius() {
+            return 0;
+        }
+    }
+
+    public static class Pair<A, B extends Comparable<B>> {
+        public A first() {
+            return null;
+        }
+
+        public void setSecond(B second) {}
+    }
+}
@@ -0,0 +1,20 @@
+use anyhow::Result;
+use rs4j::build::BindgenConfig;
+
+fn main() -> Result<()> {
+    let out = std::env::var("OUT_DIR")?;
+
+    println!("cargo:rerun-if-changed=bindings");
+
+    BindgenConfig::new()
+        .package("com.example")
+        .bindings(format!("{out}/bindings.rs"))
+        .glob(format!("{}/bindings/**/*.rs4j", env!("CARGO_MANIFEST_DIR")))?
+        .output(format!("{out}/generated"))
+        .annotations(false)
+        .instrument(true)
+        .track_leaks(true)
+        .generate()?;
+
+    Ok(())
+}
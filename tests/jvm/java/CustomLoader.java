@@ -0,0 +1,19 @@
+import java.net.URL;
+import java.net.URLClassLoader;
+
+/**
+ * Loads the natives from a class loader the system class loader can't see
+ * into, and gets objects back on a thread Rust started.
+ */
+public class CustomLoader {
+    public static void main(String[] args) throws Exception {
+        URL classes = CustomLoader.class.getProtectionDomain().getCodeSource().getLocation();
+        ClassLoader parent = ClassLoader.getPlatformClassLoader();
+
+        try (URLClassLoader loader = new URLClassLoader(new URL[] { classes }, parent)) {
+            loader.loadClass(Isolated.class.getName())
+                .getMethod("run", String.class)
+                .invoke(null, args[0]);
+        }
+    }
+}
@@ -0,0 +1,59 @@
+import com.example.Owned;
+import com.example.Tally;
+
+/**
+ * Frees objects while other threads use them, which has to throw instead of
+ * using the freed object.
+ */
+public class FreeWhileCalling {
+    public static void main(String[] args) throws Exception {
+        System.load(args[0]);
+
+        Tally tally = new Tally();
+        String[] caught = new String[1];
+
+        Thread caller = new Thread(() -> {
+            try {
+                while (true) {
+                    tally.add(1);
+                    tally.sum();
+                }
+            } catch (IllegalStateException e) {
+                caught[0] = e.getMessage();
+            }
+        });
+
+        caller.start();
+
+        while (tally.sum() < 1000) {
+            Thread.onSpinWait();
+        }
+
+        tally.free();
+        caller.join();
+
+        System.out.println(caught[0]);
+
+        try {
+            tally.sum();
+        } catch (IllegalStateException e) {
+            System.out.println(e.getMessage());
+        }
+
+        Owned owned = new Owned();
+
+        Thread freer = new Thread(() -> {
+            try {
+                owned.free();
+            } catch (IllegalStateException e) {
+                System.out.println(e.getMessage());
+            }
+        });
+
+        freer.start();
+        freer.join();
+
+        System.out.println(owned.id());
+        owned.free();
+    }
+}
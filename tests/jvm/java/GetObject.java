@@ -0,0 +1,39 @@
+import com.example.Label;
+import org.stardustmodding.rs4j.util.NativeClass;
+import org.stardustmodding.rs4j.util.NativeTools;
+
+/**
+ * Wraps pointers made in Rust with NativeTools.getObject.
+ */
+public class GetObject {
+    static native long makeLabel(String name);
+
+    /** A native class without a (long) constructor. */
+    static class Unwrappable implements NativeClass {
+        public long getPointer() {
+            return 0;
+        }
+    }
+
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        Label label = NativeTools.getObject(makeLabel("round trip"), Label.class);
+
+        System.out.println(label.getClass().getName() + ": " + label.getName());
+        label.free();
+
+        System.out.println(NativeTools.getObject(0, Label.class));
+
+        long ptr = makeLabel("leftover");
+
+        try {
+            NativeTools.getObject(ptr, Unwrappable.class);
+            System.out.println("no exception");
+        } catch (NoSuchMethodError e) {
+            System.out.println(e.getClass().getName());
+        }
+
+        NativeTools.getObject(ptr, Label.class).free();
+    }
+}
@@ -0,0 +1,17 @@
+import com.example.Frames;
+import com.example.Point;
+
+/**
+ * The part of CustomLoader that runs in its own class loader.
+ */
+public class Isolated {
+    public static void run(String library) throws Exception {
+        System.load(library);
+
+        Point[] points = { new Point(1, 2), new Point(3, 4) };
+        Point corner = Frames.corner(points).get();
+
+        System.out.println(corner);
+        System.out.println(corner.getClass().getClassLoader() == Isolated.class.getClassLoader());
+    }
+}
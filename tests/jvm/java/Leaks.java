@@ -0,0 +1,27 @@
+import com.example.Outer;
+import org.stardustmodding.rs4j.util.NativeLeaks;
+
+/**
+ * Checks that an object's fields are tracked until it's freed.
+ */
+public class Leaks {
+    static void printOuter() {
+        for (String line : NativeLeaks.report().split("\n")) {
+            if (line.contains("com.example.Outer")) {
+                System.out.println(line);
+            }
+        }
+    }
+
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        Outer outer = new Outer(7);
+
+        printOuter();
+        outer.free();
+        printOuter();
+
+        System.out.println("freed");
+    }
+}
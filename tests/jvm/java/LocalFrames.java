@@ -0,0 +1,28 @@
+import com.example.Frames;
+import com.example.Point;
+import com.example.TuplePointPointString;
+
+/**
+ * Calls one native 100k times with an array of objects, getting new objects
+ * back every time. Without local reference frames, -Xcheck:jni warns that the
+ * native's local references overflow.
+ */
+public class LocalFrames {
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        Point[] points = new Point[64];
+
+        for (int i = 0; i < points.length; i++) {
+            points[i] = new Point(i, -i);
+        }
+
+        TuplePointPointString bounds = null;
+
+        for (int i = 0; i < 100_000; i++) {
+            bounds = Frames.bounds(points);
+        }
+
+        System.out.println(bounds);
+    }
+}
@@ -0,0 +1,20 @@
+import com.example.Counter;
+
+/**
+ * Changes an object through mutable methods and a compound assignment, which
+ * have to write their changes back into it.
+ */
+public class MutableMethods {
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        Counter counter = new Counter("clicks");
+
+        counter.increment();
+        counter.increment();
+        counter.plusAssign(40);
+        counter.rename("taps");
+
+        System.out.println(counter.getName() + " " + counter.getCount());
+    }
+}
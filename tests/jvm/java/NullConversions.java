@@ -0,0 +1,35 @@
+import com.example.Clock;
+import java.nio.file.Path;
+import java.time.Duration;
+import java.time.Instant;
+
+/**
+ * Passes nulls where natives expect Durations, Instants and Paths, which
+ * throws NullPointerExceptions instead of crashing the JVM.
+ */
+public class NullConversions {
+    interface Call {
+        Object call();
+    }
+
+    static void attempt(Call call) {
+        try {
+            System.out.println(call.call());
+        } catch (NullPointerException e) {
+            System.out.println(e.getMessage());
+        }
+    }
+
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        attempt(() -> Clock.later(Instant.EPOCH, Duration.ofSeconds(90)));
+        attempt(() -> Clock.later(null, Duration.ofSeconds(90)));
+        attempt(() -> Clock.later(Instant.EPOCH, null));
+        attempt(() -> Clock.total(new Duration[] { Duration.ofSeconds(1), Duration.ofSeconds(2) }));
+        attempt(() -> Clock.total(new Duration[] { Duration.ofSeconds(1), null }));
+        attempt(() -> Clock.total(null));
+        attempt(() -> Clock.fileName(Path.of("dir", "file.txt")));
+        attempt(() -> Clock.fileName(null));
+    }
+}
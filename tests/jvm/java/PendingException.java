@@ -0,0 +1,23 @@
+import com.example.Frames;
+import com.example.Point;
+
+/**
+ * Looks for the class loader while an exception is pending, which has to be
+ * left for Java instead of being cleared.
+ */
+public class PendingException {
+    static native void throwAndRemember();
+
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        try {
+            throwAndRemember();
+            System.out.println("no exception");
+        } catch (IllegalStateException e) {
+            System.out.println(e.getMessage());
+        }
+
+        System.out.println(Frames.bounds(new Point[] { new Point(1, 2) }).third());
+    }
+}
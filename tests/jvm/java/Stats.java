@@ -0,0 +1,26 @@
+import com.example.Frames;
+import com.example.Point;
+import org.stardustmodding.rs4j.util.NativeStats;
+
+/**
+ * Counts the calls into an instrumented native.
+ */
+public class Stats {
+    public static void main(String[] args) {
+        System.load(args[0]);
+
+        NativeStats.reset();
+
+        Point[] points = { new Point(1, 2), new Point(3, 4) };
+
+        for (int i = 0; i < 3; i++) {
+            Frames.bounds(points);
+        }
+
+        for (NativeStats.Entry entry : NativeStats.snapshot()) {
+            if (entry.method().endsWith(".bounds")) {
+                System.out.println(entry.method() + ": " + entry.calls());
+            }
+        }
+    }
+}
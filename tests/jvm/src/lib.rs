@@ -0,0 +1,182 @@
+//! The natives used by the Java programs in `java/`.
+
+use std::{
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+pub struct Frames;
+
+impl Frames {
+    /// Get the corners of the box around some points, and a description of it.
+    pub fn bounds(points: &[Point]) -> (Point, Point, String) {
+        let min = Point {
+            x: points.iter().map(|it| it.x).min().unwrap_or_default(),
+            y: points.iter().map(|it| it.y).min().unwrap_or_default(),
+        };
+
+        let max = Point {
+            x: points.iter().map(|it| it.x).max().unwrap_or_default(),
+            y: points.iter().map(|it| it.y).max().unwrap_or_default(),
+        };
+
+        let label = format!("{} points", points.len());
+
+        (min, max, label)
+    }
+
+    /// Get the top left corner of the box around some points, on another
+    /// thread.
+    pub async fn corner(points: &[Point]) -> Point {
+        Self::bounds(points).0
+    }
+}
+
+pub struct Label {
+    pub name: String,
+}
+
+impl Label {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+}
+
+#[derive(Clone)]
+pub struct Inner {
+    pub value: i32,
+}
+
+#[derive(Clone)]
+pub struct Outer {
+    pub inner: Inner,
+}
+
+impl Outer {
+    pub fn new(value: i32) -> Self {
+        Self {
+            inner: Inner { value },
+        }
+    }
+}
+
+pub struct Clock;
+
+impl Clock {
+    pub fn later(time: SystemTime, by: Duration) -> SystemTime {
+        time + by
+    }
+
+    pub fn total(durations: &[Duration]) -> Duration {
+        durations.iter().sum()
+    }
+
+    pub fn file_name(path: PathBuf) -> String {
+        path.file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Counter {
+    pub count: i32,
+    pub name: String,
+}
+
+impl Counter {
+    pub fn new(name: String) -> Self {
+        Self { count: 0, name }
+    }
+
+    /// Count one more, returning the new count.
+    pub fn increment(&mut self) -> i32 {
+        self.count += 1;
+        self.count
+    }
+
+    pub fn rename(&mut self, name: String) {
+        self.name = name;
+    }
+}
+
+impl std::ops::AddAssign<i32> for Counter {
+    fn add_assign(&mut self, rhs: i32) {
+        self.count += rhs;
+    }
+}
+
+/// A total shared between threads, for `FreeWhileCalling`.
+#[derive(Debug, Default)]
+pub struct Tally {
+    pub total: i64,
+}
+
+impl Tally {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, n: i64) {
+        self.total += n;
+    }
+
+    pub fn sum(&self) -> i64 {
+        self.total
+    }
+}
+
+/// An object kept on the thread that made it, for `FreeWhileCalling`.
+#[derive(Debug, Default)]
+pub struct Owned;
+
+impl Owned {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn id(&self) -> i64 {
+        7
+    }
+}
+
+include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+
+rs4j::jni_onload!();
+
+/// Make a [`Label`] for `GetObject`, returning just its pointer.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_GetObject_makeLabel<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    name: JString<'local>,
+) -> jlong {
+    let name = env.get_string(&name).unwrap().into();
+
+    Box::into_raw(Box::new(unsafe { __JNI_Label::of(Label::new(name)) })) as jlong
+}
+
+/// Throw an exception, then look for the class loader again with it still
+/// pending, for `PendingException`.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_PendingException_throwAndRemember<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) {
+    clear_cache();
+
+    env.throw_new(
+        "java/lang/IllegalStateException",
+        "thrown before the lookup",
+    )
+    .unwrap();
+
+    remember_class_loader(&mut env);
+}
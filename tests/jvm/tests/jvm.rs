@@ -0,0 +1,213 @@
+//! Runs the Java programs in `java/` against the natives, under `-Xcheck:jni`,
+//! so anything the JVM warns about fails the test.
+//!
+//! These need a JDK, found through `JAVA_HOME` or the `PATH`. Without one,
+//! they're skipped.
+
+use std::{
+    env::consts::{DLL_PREFIX, DLL_SUFFIX},
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// Get a JDK tool, like `javac`.
+fn tool(name: &str) -> PathBuf {
+    match std::env::var_os("JAVA_HOME") {
+        Some(home) => Path::new(&home).join("bin").join(name),
+        None => name.into(),
+    }
+}
+
+/// Run a command, or return [`None`] if it doesn't exist.
+fn output(cmd: &mut Command) -> Option<Output> {
+    match cmd.output() {
+        Ok(it) => Some(it),
+        Err(err) if err.kind() == ErrorKind::NotFound => None,
+        Err(err) => panic!("Couldn't run {cmd:?}: {err}"),
+    }
+}
+
+/// Get the natives, which are built into the `deps` directory along with
+/// this test.
+fn library() -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+
+    exe.with_file_name(format!("{DLL_PREFIX}jvm_tests{DLL_SUFFIX}"))
+}
+
+/// Compile and run `java/{main}.java` with the generated classes, returning
+/// what it printed, or [`None`] if there's no JDK.
+fn run(main: &str) -> Option<String> {
+    let java = Path::new(env!("CARGO_MANIFEST_DIR")).join("java");
+    let generated = Path::new(env!("OUT_DIR")).join("generated").join("java");
+    let classes = Path::new(env!("CARGO_TARGET_TMPDIR")).join(main);
+    let source_path = std::env::join_paths([&java, &generated]).unwrap();
+
+    let Some(javac) = output(
+        Command::new(tool("javac"))
+            .arg("-d")
+            .arg(&classes)
+            .arg("-sourcepath")
+            .arg(source_path)
+            .arg(java.join(format!("{main}.java"))),
+    ) else {
+        eprintln!("No JDK found, skipping {main}");
+        return None;
+    };
+
+    assert!(
+        javac.status.success(),
+        "Couldn't compile {main}:\n{}",
+        String::from_utf8_lossy(&javac.stderr)
+    );
+
+    let out = output(
+        Command::new(tool("java"))
+            .arg("-Xcheck:jni")
+            .arg("-cp")
+            .arg(&classes)
+            .arg(main)
+            .arg(library()),
+    )?;
+
+    let stdout = String::from_utf8_lossy(&out.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&out.stderr);
+
+    assert!(out.status.success(), "{main} failed:\n{stdout}\n{stderr}");
+
+    // Like `WARNING: JNI local refs: 33, exceeds capacity: 32`, which HotSpot
+    // prints to stdout.
+    let warned = [
+        "WARNING: JNI",
+        "WARNING in native method",
+        "FATAL ERROR in native method",
+    ]
+    .iter()
+    .any(|it| stdout.contains(it) || stderr.contains(it));
+
+    assert!(!warned, "The JVM warned about {main}:\n{stdout}\n{stderr}");
+
+    Some(stdout)
+}
+
+#[test]
+fn local_frames() {
+    let Some(out) = run("LocalFrames") else {
+        return;
+    };
+
+    assert_eq!(
+        out.trim(),
+        "TuplePointPointString[first=Point[x=0, y=-63], second=Point[x=63, y=0], third=64 points]"
+    );
+}
+
+#[test]
+fn get_object() {
+    let Some(out) = run("GetObject") else {
+        return;
+    };
+
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        [
+            "com.example.Label: round trip",
+            "null",
+            "java.lang.NoSuchMethodError"
+        ]
+    );
+}
+
+#[test]
+fn stats() {
+    let Some(out) = run("Stats") else {
+        return;
+    };
+
+    assert_eq!(out.trim(), "com.example.Frames.bounds: 3");
+}
+
+#[test]
+fn leaks() {
+    let Some(out) = run("Leaks") else {
+        return;
+    };
+
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        [
+            "  com.example.Outer: 1",
+            "  com.example.Outer.inner: 1",
+            "freed"
+        ]
+    );
+}
+
+#[test]
+fn custom_loader() {
+    let Some(out) = run("CustomLoader") else {
+        return;
+    };
+
+    assert_eq!(out.lines().collect::<Vec<_>>(), ["Point[x=1, y=2]", "true"]);
+}
+
+#[test]
+fn pending_exception() {
+    let Some(out) = run("PendingException") else {
+        return;
+    };
+
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        ["thrown before the lookup", "1 points"]
+    );
+}
+
+#[test]
+fn null_conversions() {
+    let Some(out) = run("NullConversions") else {
+        return;
+    };
+
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        [
+            "1970-01-01T00:01:30Z",
+            "Can't convert a null java.time.Instant",
+            "Can't convert a null java.time.Duration",
+            "PT3S",
+            "Can't convert a null java.time.Duration",
+            "Can't convert a null array",
+            "file.txt",
+            "Can't convert a null java.nio.file.Path",
+        ]
+    );
+}
+
+#[test]
+fn mutable_methods() {
+    let Some(out) = run("MutableMethods") else {
+        return;
+    };
+
+    assert_eq!(out.trim(), "taps 42");
+}
+
+#[test]
+fn free_while_calling() {
+    let Some(out) = run("FreeWhileCalling") else {
+        return;
+    };
+
+    assert_eq!(
+        out.lines().collect::<Vec<_>>(),
+        [
+            "com.example.Tally was already freed",
+            "com.example.Tally was already freed",
+            "com.example.Owned can only be used from the thread that created it",
+            "7"
+        ]
+    );
+}